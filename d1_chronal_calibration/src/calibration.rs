@@ -0,0 +1,406 @@
+use crate::frequency::{Frequency, Overflow};
+use std::collections::HashSet;
+
+/// A frequency that the device reads for the second time.
+///
+/// `pass` counts how many times the change list has been fully applied
+/// before the repeat (0 = first pass), and `index` is the position in the
+/// change list of the change that produced the repeated frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat<F> {
+    pub frequency: F,
+    pub pass: u128,
+    pub index: usize,
+}
+
+/// Position of a change in the (endlessly repeated) change list.
+///
+/// Steps order chronologically, since `pass` is compared first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Step {
+    pub pass: u128,
+    pub index: usize,
+}
+
+impl Step {
+    pub(crate) fn overflow(self) -> Overflow {
+        Overflow {
+            pass: self.pass,
+            line: self.index + 1,
+        }
+    }
+}
+
+/// Why a frequency can never be read by the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unreachable<F> {
+    /// There are no changes, so the device stays at 0 forever.
+    NoChanges,
+    /// The net drift is 0 and the target isn't one of the frequencies
+    /// read during a pass, which every later pass repeats exactly.
+    NotInCycle,
+    /// No frequency read during the first pass is congruent to the target
+    /// modulo the drift, and each pass only shifts them by the drift.
+    WrongResidue { residue: u128, drift: F },
+    /// Frequencies with the right residue exist, but all of them lie past
+    /// the target in the direction of the drift. `nearest` is the closest one.
+    WrongDirection { nearest: F, drift: F },
+    /// The running frequency leaves the integer range before the target
+    /// is read.
+    Overflow(Overflow),
+}
+
+/*  Closed form for the first repeated frequency.
+
+The first repeat is the earliest step from which some change keeps
+producing repeats (see `repeat_passes`), so it only takes one sort over
+the first pass however many passes the device would need.
+
+Returns `Err` if the running frequency would overflow `F` before the
+repeat is reached. Lists that never repeat give `Ok(None)`, even though
+the device itself would overflow eventually.*/
+pub fn first_repeat<F: Frequency>(changes: &[F]) -> Result<Option<Repeat<F>>, Overflow> {
+    let n = changes.len();
+    if n == 0 {
+        return Ok(None);
+    }
+
+    let (after, overflow) = running_sums(changes);
+
+    if let Some(overflow) = overflow {
+        // Only a repeat before the overflowing line can still be reported
+        return first_pass_repeat(&after).map(Some).ok_or(overflow);
+    }
+
+    let drift = after[n - 1];
+    let step = repeat_passes(&after)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, pass)| {
+            Some(Step {
+                pass: pass?,
+                index: idx,
+            })
+        })
+        .min();
+
+    let Some(step) = step else {
+        return Ok(None);
+    };
+
+    if let Some(overflow) = first_overflow(&after, drift).filter(|&at| at <= step) {
+        return Err(overflow.overflow());
+    }
+
+    let frequency = after[step.index]
+        .checked_add_times(drift, step.pass)
+        .ok_or(step.overflow())?;
+
+    Ok(Some(Repeat {
+        frequency,
+        pass: step.pass,
+        index: step.index,
+    }))
+}
+
+/*  For each change, the first pass from which the frequency it produces is
+always one that was read before (`None` if that never happens).
+
+Let `t[j]` be the frequency just before applying change `j` on the first
+pass (so `t[0] = 0`), and `drift` the sum of all changes. The frequency
+before change `j` on pass `p` is then `t[j] + p * drift`.
+
+A later value can only ever equal an earlier one if both share the same
+residue modulo `drift`. Within one residue class, the first value that
+`t[j]` runs into is the next one along the direction of the drift, which it
+reaches after `k = gap / |drift|` passes, and it follows that value's trail
+from then on. Values before that are all new. A gap of 0 means the value
+was already read earlier in the first pass.
+
+When `drift == 0` every pass reads the same values as the first one.
+
+`after` must be the complete (non-overflowing) first pass.*/
+pub(crate) fn repeat_passes<F: Frequency>(after: &[F]) -> Vec<Option<u128>> {
+    let n = after.len();
+    let drift = after[n - 1];
+
+    // Frequencies seen before each change on the first pass
+    let mut before: Vec<F> = Vec::with_capacity(n);
+    before.push(F::ZERO);
+    before.extend_from_slice(&after[..n - 1]);
+
+    let mut passes: Vec<Option<u128>> = vec![None; n];
+
+    if drift == F::ZERO {
+        let mut seen = HashSet::new();
+
+        for (idx, &freq) in before.iter().enumerate() {
+            passes[idx] = Some(if seen.insert(freq) { 1 } else { 0 });
+        }
+    } else {
+        // Group by residue, then order each group by frequency
+        let modulus = drift.unsigned_abs();
+        let mut groups: Vec<(u128, F, usize)> = before
+            .iter()
+            .enumerate()
+            .map(|(idx, &freq)| (freq.residue(modulus), freq, idx))
+            .collect();
+        groups.sort_unstable();
+
+        for (pos, &(residue, freq, idx)) in groups.iter().enumerate() {
+            let same_class = |&&(r, _, _): &&(u128, F, usize)| r == residue;
+
+            if pos > 0 && groups[pos - 1].0 == residue && groups[pos - 1].1 == freq {
+                // Same frequency seen earlier in the first pass
+                passes[idx] = Some(0);
+                continue;
+            }
+
+            // Next distinct frequency in the direction of drift
+            let next = if drift.is_negative() {
+                groups[..pos]
+                    .iter()
+                    .rev()
+                    .take_while(same_class)
+                    .find(|&&(_, f, _)| f != freq)
+            } else {
+                groups[pos + 1..]
+                    .iter()
+                    .take_while(same_class)
+                    .find(|&&(_, f, _)| f != freq)
+            };
+
+            passes[idx] = next.map(|&(_, next_freq, _)| next_freq.abs_diff(freq) / modulus);
+        }
+    }
+
+    // Shift from "before change `j`" to "after change `j - 1`"; the value
+    // before change 0 on pass `p` is the one after the last change on `p - 1`
+    passes.rotate_left(1);
+    passes[n - 1] = passes[n - 1].map(|pass| pass - 1);
+
+    passes
+}
+
+// Naive search for a repeat among the running sums of the first pass
+fn first_pass_repeat<F: Frequency>(after: &[F]) -> Option<Repeat<F>> {
+    let mut seen = HashSet::from([F::ZERO]);
+
+    after
+        .iter()
+        .enumerate()
+        .find(|&(_, &freq)| !seen.insert(freq))
+        .map(|(idx, &freq)| Repeat {
+            frequency: freq,
+            pass: 0,
+            index: idx,
+        })
+}
+
+/*  Earliest step at which the device reads `target`.
+
+After change `j` on pass `p` the device reads `s[j] + p * drift`, where
+`s[j]` is the running sum on the first pass. So `target` is read at
+(p, j) iff `target - s[j]` is a non-negative multiple of `drift`, and the
+earliest step is the one minimising (p, j).
+
+The starting frequency 0 is not a step: a target of 0 reports the first
+time the device gets back to 0. If the running frequency overflows `F`
+on a later pass before `target` is read, that is reported instead, but a
+target that could never be read is reported as unreachable. If the first
+pass itself overflows, the drift is unknown: only targets read before the
+overflow are found, and any other target gives `Overflow`.*/
+pub fn first_reach<F: Frequency>(changes: &[F], target: F) -> Result<Step, Unreachable<F>> {
+    let n = changes.len();
+    if n == 0 {
+        return Err(Unreachable::NoChanges);
+    }
+
+    let (after, overflow) = running_sums(changes);
+    let first_pass = after
+        .iter()
+        .position(|&freq| freq == target)
+        .map(|index| Step { pass: 0, index });
+
+    if let Some(overflow) = overflow {
+        return first_pass.ok_or(Unreachable::Overflow(overflow));
+    }
+
+    let drift = after[n - 1];
+
+    if drift == F::ZERO {
+        return first_pass.ok_or(Unreachable::NotInCycle);
+    }
+
+    let modulus = drift.unsigned_abs();
+    let mut best: Option<Step> = None;
+    let mut nearest: Option<F> = None;
+
+    for (idx, &freq) in after.iter().enumerate() {
+        let gap = target.abs_diff(freq);
+        if gap % modulus != 0 {
+            continue;
+        }
+
+        if gap != 0 && (target < freq) != drift.is_negative() {
+            // Same residue, but the drift carries it away from `target`
+            if nearest.is_none_or(|near| target.abs_diff(near) > gap) {
+                nearest = Some(freq);
+            }
+            continue;
+        }
+
+        let step = Step {
+            pass: gap / modulus,
+            index: idx,
+        };
+
+        if best.is_none_or(|best_step| step < best_step) {
+            best = Some(step);
+        }
+    }
+
+    match (best, nearest) {
+        (Some(step), _) => match first_overflow(&after, drift) {
+            Some(at) if at < step => Err(Unreachable::Overflow(at.overflow())),
+            _ => Ok(step),
+        },
+        (None, Some(nearest)) => Err(Unreachable::WrongDirection { nearest, drift }),
+        (None, None) => Err(Unreachable::WrongResidue {
+            residue: target.residue(modulus),
+            drift,
+        }),
+    }
+}
+
+// Frequencies after each change on the first pass, stopping early if the
+// running sum overflows
+pub(crate) fn running_sums<F: Frequency>(changes: &[F]) -> (Vec<F>, Option<Overflow>) {
+    let mut after: Vec<F> = Vec::with_capacity(changes.len());
+    let mut curr_sum = F::ZERO;
+
+    for (idx, &chg) in changes.iter().enumerate() {
+        match curr_sum.checked_add(chg) {
+            Some(sum) => curr_sum = sum,
+            None => {
+                return (
+                    after,
+                    Some(Overflow {
+                        pass: 0,
+                        line: idx + 1,
+                    }),
+                )
+            }
+        }
+        after.push(curr_sum);
+    }
+
+    (after, None)
+}
+
+/*  Earliest step on a later pass at which the frequency leaves `F`'s range.
+
+Each pass moves every running sum by `drift`, so `s[j]` has
+`|bound - s[j]| / |drift|` passes of headroom before it crosses the bound
+in the direction of the drift. `None` if that is never (zero drift) or
+further out than a `u128` pass count can express.*/
+pub(crate) fn first_overflow<F: Frequency>(after: &[F], drift: F) -> Option<Step> {
+    if drift == F::ZERO {
+        return None;
+    }
+
+    let bound = if drift.is_negative() { F::MIN } else { F::MAX };
+    let modulus = drift.unsigned_abs();
+
+    after
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &freq)| {
+            let pass = (bound.abs_diff(freq) / modulus).checked_add(1)?;
+            Some(Step { pass, index: idx })
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        calibration::{first_reach, first_repeat, Step, Unreachable},
+        frequency::Overflow,
+    };
+
+    #[test]
+    fn test_first_repeat() {
+        let tests: Vec<(Vec<i32>, i32)> = vec![
+            (vec![1, -1], 0),
+            (vec![3, 3, 4, -2, -4], 10),
+            (vec![-6, 3, 8, 5, -6], 5),
+            (vec![7, 7, -2, -7, -4], 14),
+        ];
+
+        for (changes, repeated) in tests {
+            let repeat = first_repeat(&changes).unwrap().unwrap();
+            assert_eq!(repeated, repeat.frequency);
+        }
+    }
+
+    #[test]
+    fn test_never_repeats() {
+        assert_eq!(None, first_repeat(&[1, 1]).unwrap());
+        assert_eq!(None, first_repeat::<i32>(&[]).unwrap());
+    }
+
+    #[test]
+    fn test_first_reach() {
+        let changes = [3, 3, 4, -2, -4];
+
+        assert_eq!(Ok(Step { pass: 0, index: 3 }), first_reach(&changes, 8));
+        assert_eq!(Ok(Step { pass: 1, index: 2 }), first_reach(&changes, 14));
+        assert_eq!(Ok(Step { pass: 2, index: 0 }), first_reach(&changes, 11));
+        assert_eq!(
+            Err(Unreachable::WrongResidue {
+                residue: 1,
+                drift: 4
+            }),
+            first_reach(&changes, 5)
+        );
+        assert_eq!(
+            Err(Unreachable::WrongDirection {
+                nearest: 4,
+                drift: 4
+            }),
+            first_reach(&changes, 0)
+        );
+    }
+
+    #[test]
+    fn test_first_reach_zero_drift() {
+        assert_eq!(Ok(Step { pass: 0, index: 1 }), first_reach(&[1, -1], 0));
+        assert_eq!(Err(Unreachable::NotInCycle), first_reach(&[1, -1], 5));
+        assert_eq!(Err(Unreachable::NoChanges), first_reach::<i32>(&[], 0));
+    }
+
+    #[test]
+    fn test_first_reach_overflow() {
+        // Index 0 leaves `i32` on pass 147483648, long before 200000000 is
+        // read at index 1
+        let changes = [2_000_000_000, -1_999_999_999];
+
+        assert_eq!(Ok(Step { pass: 4, index: 1 }), first_reach(&changes, 5));
+        assert_eq!(
+            Err(Unreachable::Overflow(Overflow {
+                pass: 147_483_648,
+                line: 1
+            })),
+            first_reach(&changes, 200_000_000)
+        );
+
+        // The first pass overflows on line 2: 1 is read before that, 2 isn't
+        let changes = [1, i32::MAX, 5];
+        assert_eq!(Ok(Step { pass: 0, index: 0 }), first_reach(&changes, 1));
+        assert_eq!(
+            Err(Unreachable::Overflow(Overflow { pass: 0, line: 2 })),
+            first_reach(&changes, 2)
+        );
+    }
+}
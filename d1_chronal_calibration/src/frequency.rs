@@ -0,0 +1,99 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    str::FromStr,
+};
+
+/// Integer type the calibration engine can accumulate frequencies in.
+///
+/// Anything that isn't a plain checked addition is done on the magnitude as
+/// a `u128`, which is wide enough for the difference of any two values of
+/// every implementing type.
+pub trait Frequency: Copy + Ord + Hash + Debug + Display + FromStr {
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn is_negative(self) -> bool;
+    fn unsigned_abs(self) -> u128;
+    fn abs_diff(self, other: Self) -> u128;
+
+    /// `self + times * step`, or `None` if the result doesn't fit.
+    fn checked_add_times(self, step: Self, times: u128) -> Option<Self>;
+
+    /// Residue of `self` modulo `modulus`, always in `0..modulus`.
+    fn residue(self, modulus: u128) -> u128 {
+        let rem = self.unsigned_abs() % modulus;
+
+        if self.is_negative() && rem != 0 {
+            modulus - rem
+        } else {
+            rem
+        }
+    }
+}
+
+macro_rules! impl_frequency {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl Frequency for $t {
+                const ZERO: Self = 0;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn is_negative(self) -> bool {
+                    self < 0
+                }
+
+                fn unsigned_abs(self) -> u128 {
+                    <$t>::unsigned_abs(self) as u128
+                }
+
+                fn abs_diff(self, other: Self) -> u128 {
+                    <$t>::abs_diff(self, other) as u128
+                }
+
+                fn checked_add_times(self, step: Self, times: u128) -> Option<Self> {
+                    let magnitude = times.checked_mul(Frequency::unsigned_abs(step))?;
+                    let magnitude = <$u>::try_from(magnitude).ok()?;
+
+                    if step < 0 {
+                        self.checked_sub_unsigned(magnitude)
+                    } else {
+                        self.checked_add_unsigned(magnitude)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_frequency!(i32 => u32, i64 => u64, i128 => u128);
+
+/// The running frequency no longer fits the chosen integer width.
+///
+/// `pass` is 0-based; `line` is the 1-based line of the change list whose
+/// change pushed the frequency out of range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub pass: u128,
+    pub line: usize,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frequency overflowed on pass {} at line {}",
+            self.pass, self.line
+        )
+    }
+}
+
+impl Error for Overflow {}
@@ -0,0 +1,11 @@
+pub mod calibration;
+pub mod frequency;
+pub mod parse;
+pub mod stats;
+pub mod trace;
+
+pub use calibration::{first_reach, first_repeat, Repeat, Step, Unreachable};
+pub use frequency::{Frequency, Overflow};
+pub use parse::{parse_changes, ParseError, ParseErrorKind, ParseMode, ParsedChanges};
+pub use stats::{repeat_stats, RepeatStats};
+pub use trace::{write_trace, TraceError, TraceFormat, TraceOptions, TraceRow};
@@ -1,47 +1,76 @@
-use std::{
-    collections::HashSet,
-    fs,
-    io::{BufRead, BufReader},
+use day1_chronal_calibration::{
+    first_repeat, parse_changes, write_trace, ParseMode, TraceError, TraceFormat, TraceOptions,
 };
+use std::{env, fs, io, process};
 
 fn main() {
+    // Usage: day1_chronal_calibration [--lenient] [--trace csv|jsonl [max_rows]]
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mode = match args.iter().position(|arg| arg == "--lenient") {
+        Some(idx) => {
+            args.remove(idx);
+            ParseMode::Lenient
+        }
+        None => ParseMode::Strict,
+    };
+
     // Read input .txt
-    let file = fs::File::open("input.txt").expect("Failed to open input");
-    let reader = BufReader::new(file);
+    let input = fs::read_to_string("input.txt").expect("Failed to open input");
 
     //Convert file contents into Vec
-    let mut input_as_vec: Vec<i32> = Vec::new();
-    
-    for line in reader.lines() {
-        let parsed_chg = line
-        .expect("Failed to read line")
-        .parse::<i32>()
-        .expect("Failed to parse i32");
-    
-        input_as_vec.push(parsed_chg);
+    let parsed = match parse_changes::<i64>(&input, mode) {
+        Ok(parsed) => parsed,
+        Err(errors) => {
+            for err in errors {
+                eprintln!("{}", err);
+            }
+            process::exit(1);
+        }
+    };
+
+    for err in &parsed.errors {
+        eprintln!("Skipped {}", err);
     }
 
-    // Loop until repeated freq found
-    let mut found = false;
-    let mut curr_sum = 0;
-    let mut prev_sums: HashSet<i32> = HashSet::new();
-    
-    prev_sums.insert(curr_sum);
-    while !found {
-        
-        for num in &input_as_vec {
-            curr_sum += num;
-
-            if !prev_sums.insert(curr_sum) {
-                println!("{}", curr_sum);
-                found = true;
-                break;
+    let input_as_vec = parsed.changes;
+    let input_lines = parsed.lines;
+
+    if args.first().map(String::as_str) == Some("--trace") {
+        let format = match args.get(1).map(String::as_str) {
+            Some("csv") => TraceFormat::Csv,
+            Some("jsonl") => TraceFormat::JsonLines,
+            other => {
+                eprintln!(
+                    "Unknown trace format {:?}, expected csv or jsonl",
+                    other.unwrap_or("")
+                );
+                process::exit(1);
             }
+        };
+        let max_rows = args
+            .get(2)
+            .map(|rows| rows.parse::<usize>().expect("Failed to parse max_rows"));
+
+        let options = TraceOptions { format, max_rows };
+        if let Err(mut err) = write_trace(&input_as_vec, options, &mut io::stdout().lock()) {
+            // Report the line in input.txt rather than in the change list
+            if let TraceError::Overflow(overflow) = &mut err {
+                overflow.line = input_lines[overflow.line - 1];
+            }
+            eprintln!("Trace failed: {}", err);
+            process::exit(1);
         }
+        return;
     }
-}
 
-// TEST INPUTS
-//let input_as_vec: Vec<i32> = vec![1, -1];
-//let input_as_vec: Vec<i32> = vec![3, 3, 4, -2, -4];
-//let input_as_vec: Vec<i32> = vec![7, 7, -2, -7, -4];
\ No newline at end of file
+    // Find first repeated freq
+    match first_repeat(&input_as_vec) {
+        Ok(Some(repeat)) => println!("{}", repeat.frequency),
+        Ok(None) => println!("No frequency is ever repeated"),
+        Err(mut overflow) => {
+            // Report the line in input.txt rather than in the change list
+            overflow.line = input_lines[overflow.line - 1];
+            println!("Calibration failed: {}", overflow);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::frequency::Frequency;
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Any malformed line fails the whole parse.
+    Strict,
+    /// Malformed lines are skipped and reported alongside the changes.
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A `+` or `-` with no number after it.
+    MissingNumber,
+    /// Something other than an optionally signed run of digits.
+    InvalidNumber,
+    /// Digits that don't fit the frequency type.
+    OutOfRange,
+}
+
+/// A malformed line. `line` is 1-based; `text` is the line as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            ParseErrorKind::MissingNumber => "sign without a number",
+            ParseErrorKind::InvalidNumber => "not a number",
+            ParseErrorKind::OutOfRange => "number out of range",
+        };
+
+        write!(f, "line {}: {} in {:?}", self.line, reason, self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Changes parsed from an input file.
+///
+/// `lines[i]` is the 1-based input line that `changes[i]` came from, since
+/// blank lines, comments and skipped lines make the two diverge. `errors`
+/// lists the lines skipped in lenient mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedChanges<F> {
+    pub changes: Vec<F>,
+    pub lines: Vec<usize>,
+    pub errors: Vec<ParseError>,
+}
+
+/*  Parses one change per line: `+N`, `-N` or `N`.
+
+Whitespace around the number and between sign and digits is ignored, as is
+anything after a `#`. Blank (or comment-only) lines are skipped.
+
+Every line is checked before returning, so in strict mode `Err` holds all
+the malformed lines at once.*/
+pub fn parse_changes<F: Frequency>(
+    input: &str,
+    mode: ParseMode,
+) -> Result<ParsedChanges<F>, Vec<ParseError>> {
+    let mut parsed = ParsedChanges {
+        changes: Vec::new(),
+        lines: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for (idx, text) in input.lines().enumerate() {
+        let content = match text.split_once('#') {
+            Some((before, _)) => before,
+            None => text,
+        }
+        .trim();
+
+        if content.is_empty() {
+            continue;
+        }
+
+        match parse_change(content) {
+            Ok(chg) => {
+                parsed.changes.push(chg);
+                parsed.lines.push(idx + 1);
+            }
+            Err(kind) => parsed.errors.push(ParseError {
+                line: idx + 1,
+                text: text.to_owned(),
+                kind,
+            }),
+        }
+    }
+
+    if mode == ParseMode::Strict && !parsed.errors.is_empty() {
+        return Err(parsed.errors);
+    }
+
+    Ok(parsed)
+}
+
+fn parse_change<F: Frequency>(content: &str) -> Result<F, ParseErrorKind> {
+    let (negative, digits) = match content.as_bytes()[0] {
+        b'+' => (false, content[1..].trim_start()),
+        b'-' => (true, content[1..].trim_start()),
+        _ => (false, content),
+    };
+
+    if digits.is_empty() {
+        return Err(ParseErrorKind::MissingNumber);
+    }
+
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseErrorKind::InvalidNumber);
+    }
+
+    let number = if negative {
+        format!("-{}", digits)
+    } else {
+        digits.to_owned()
+    };
+
+    number.parse::<F>().map_err(|_| ParseErrorKind::OutOfRange)
+}
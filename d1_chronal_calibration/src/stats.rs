@@ -0,0 +1,236 @@
+use crate::{
+    calibration::{first_overflow, repeat_passes, running_sums, Repeat, Step},
+    frequency::{Frequency, Overflow},
+};
+use std::collections::BTreeSet;
+
+/*  Long-run repetition behaviour of a change list.
+
+Each change starts producing repeats from some pass on and keeps doing so
+forever (see `repeat_passes`), or never repeats at all. So once the last of
+those passes has gone by, every pass repeats at exactly the same changes:
+
+`periodic_from`:    first pass from which the repeat pattern is the same
+                    on every pass
+`cycle_length`:     period of that pattern in steps, i.e. one pass
+`repeats_per_pass`: number of repeated readings on each of those passes
+`distinct_before_periodic`: distinct frequencies read (including the
+                    starting 0) before `periodic_from`, or `None` if there
+                    are more than a `u128` can count*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepeatStats<F> {
+    pub drift: F,
+    pub periodic_from: u128,
+    pub cycle_length: usize,
+    pub repeats_per_pass: usize,
+    pub distinct_before_periodic: Option<u128>,
+    pub repeats: Vec<Repeat<F>>,
+}
+
+/// Analyses `changes` and lists the first `k` repeated readings in order.
+///
+/// Works from one sort of the first pass, without simulating the device.
+/// `Err` if the running frequency overflows before the `k`-th repeat (or
+/// during the first pass).
+pub fn repeat_stats<F: Frequency>(changes: &[F], k: usize) -> Result<RepeatStats<F>, Overflow> {
+    if changes.is_empty() {
+        return Ok(RepeatStats {
+            drift: F::ZERO,
+            periodic_from: 0,
+            cycle_length: 0,
+            repeats_per_pass: 0,
+            distinct_before_periodic: Some(1),
+            repeats: Vec::new(),
+        });
+    }
+
+    let (after, overflow) = running_sums(changes);
+    if let Some(overflow) = overflow {
+        return Err(overflow);
+    }
+
+    let drift = after[after.len() - 1];
+    let passes = repeat_passes(&after);
+    let periodic_from = passes.iter().flatten().copied().max().unwrap_or(0);
+
+    let distinct_before_periodic = passes
+        .iter()
+        .map(|pass| pass.unwrap_or(periodic_from).min(periodic_from))
+        .try_fold(1u128, u128::checked_add);
+
+    Ok(RepeatStats {
+        drift,
+        periodic_from,
+        cycle_length: changes.len(),
+        repeats_per_pass: passes.iter().flatten().count(),
+        distinct_before_periodic,
+        repeats: first_repeats(&after, &passes, k)?,
+    })
+}
+
+// Walks the passes in order, adding each change to the active set once it
+// starts repeating. Every pass from the first repeat on has at least one
+// active change, so this takes at most `k` passes.
+fn first_repeats<F: Frequency>(
+    after: &[F],
+    passes: &[Option<u128>],
+    k: usize,
+) -> Result<Vec<Repeat<F>>, Overflow> {
+    let drift = after[after.len() - 1];
+    let overflow = first_overflow(after, drift);
+
+    let mut pending: Vec<(u128, usize)> = passes
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, pass)| Some(((*pass)?, idx)))
+        .collect();
+    pending.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut repeats: Vec<Repeat<F>> = Vec::new();
+    let mut active: BTreeSet<usize> = BTreeSet::new();
+
+    let Some(&(mut pass, _)) = pending.last() else {
+        return Ok(repeats);
+    };
+
+    while repeats.len() < k {
+        while let Some(&(start, idx)) = pending.last() {
+            if start > pass {
+                break;
+            }
+            active.insert(idx);
+            pending.pop();
+        }
+
+        for &idx in active.iter().take(k - repeats.len()) {
+            let step = Step { pass, index: idx };
+
+            if let Some(at) = overflow.filter(|&at| at <= step) {
+                return Err(at.overflow());
+            }
+
+            repeats.push(Repeat {
+                frequency: after[idx]
+                    .checked_add_times(drift, pass)
+                    .ok_or(step.overflow())?,
+                pass,
+                index: idx,
+            });
+        }
+
+        pass += 1;
+    }
+
+    Ok(repeats)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        calibration::{repeat_passes, running_sums},
+        stats::repeat_stats,
+    };
+    use std::collections::HashSet;
+
+    const PASSES: u128 = 40;
+
+    // Runs the device for `PASSES` passes, returning whether each step read
+    // a frequency seen before
+    fn simulate(changes: &[i32]) -> Vec<Vec<bool>> {
+        let mut seen = HashSet::from([0]);
+        let mut freq = 0;
+
+        (0..PASSES)
+            .map(|_| {
+                changes
+                    .iter()
+                    .map(|chg| {
+                        freq += chg;
+                        !seen.insert(freq)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn check_repeat_passes(changes: &[i32]) {
+        let simulated = simulate(changes);
+        let (after, _) = running_sums(changes);
+
+        for (idx, pass) in repeat_passes(&after).into_iter().enumerate() {
+            let expected = (0..PASSES)
+                .rev()
+                .take_while(|&pass| simulated[pass as usize][idx])
+                .last();
+
+            assert_eq!(expected, pass, "change {} of {:?}", idx, changes);
+        }
+    }
+
+    #[test]
+    fn test_repeat_passes_positive_drift() {
+        check_repeat_passes(&[3, 3, 4, -2, -4]);
+        check_repeat_passes(&[7, 7, -2, -7, -4]);
+        check_repeat_passes(&[1, 1]);
+    }
+
+    #[test]
+    fn test_repeat_passes_negative_drift() {
+        check_repeat_passes(&[-3, -3, -4, 2, 4]);
+        check_repeat_passes(&[-6, 3, 8, 5, -6, -5]);
+        check_repeat_passes(&[-1, -1]);
+    }
+
+    #[test]
+    fn test_repeat_passes_zero_drift() {
+        check_repeat_passes(&[1, -1]);
+        check_repeat_passes(&[3, -1, -1, 2, -3]);
+        check_repeat_passes(&[0]);
+    }
+
+    #[test]
+    fn test_repeat_stats() {
+        let changes = [7, 7, -2, -7, -4];
+        let stats = repeat_stats(&changes, 10).unwrap();
+
+        let expected: Vec<(u128, usize)> = simulate(&changes)
+            .iter()
+            .enumerate()
+            .flat_map(|(pass, steps)| {
+                steps
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &repeated)| repeated)
+                    .map(move |(idx, _)| (pass as u128, idx))
+            })
+            .take(10)
+            .collect();
+        let repeats: Vec<(u128, usize)> = stats
+            .repeats
+            .iter()
+            .map(|repeat| (repeat.pass, repeat.index))
+            .collect();
+
+        assert_eq!(expected, repeats);
+        assert_eq!(14, stats.repeats[0].frequency);
+        assert_eq!(1, stats.drift);
+
+        let mut freq = 0;
+        let mut distinct = HashSet::from([0]);
+        for _ in 0..stats.periodic_from {
+            for chg in changes {
+                freq += chg;
+                distinct.insert(freq);
+            }
+        }
+        assert_eq!(Some(distinct.len() as u128), stats.distinct_before_periodic);
+    }
+
+    #[test]
+    fn test_repeat_stats_uncountable() {
+        let stats = repeat_stats::<i128>(&[i128::MIN, i128::MAX, 2], 1).unwrap();
+
+        assert_eq!(None, stats.distinct_before_periodic);
+        assert_eq!(1, stats.repeats.len());
+    }
+}
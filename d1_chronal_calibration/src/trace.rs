@@ -0,0 +1,171 @@
+use crate::{
+    calibration::{first_repeat, Step},
+    frequency::{Frequency, Overflow},
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    io::{self, Write},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    Csv,
+    JsonLines,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceOptions {
+    pub format: TraceFormat,
+    /// Stop after this many rows. With no cap, the trace ends on the row of
+    /// the first repeat, or after one pass if nothing ever repeats.
+    pub max_rows: Option<usize>,
+}
+
+/// One applied change.
+///
+/// `first_seen_at` is the step where `frequency` was first read (this row's
+/// own step if it is new), or `None` for the starting frequency 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceRow<F> {
+    pub pass: u128,
+    pub index: usize,
+    pub change: F,
+    pub frequency: F,
+    pub first_seen_at: Option<Step>,
+    pub first_repeat: bool,
+}
+
+#[derive(Debug)]
+pub enum TraceError {
+    Io(io::Error),
+    Overflow(Overflow),
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::Io(err) => write!(f, "failed to write trace: {}", err),
+            TraceError::Overflow(overflow) => write!(f, "{}", overflow),
+        }
+    }
+}
+
+impl Error for TraceError {}
+
+impl From<io::Error> for TraceError {
+    fn from(err: io::Error) -> Self {
+        TraceError::Io(err)
+    }
+}
+
+/// Applies `changes` over and over like the device does, writing a row per
+/// change to `out`. Returns the number of rows written.
+pub fn write_trace<F: Frequency, W: Write>(
+    changes: &[F],
+    options: TraceOptions,
+    out: &mut W,
+) -> Result<usize, TraceError> {
+    if changes.is_empty() {
+        return Ok(0);
+    }
+
+    let max_rows = match options.max_rows {
+        Some(max) => max,
+        None => match first_repeat(changes).map_err(TraceError::Overflow)? {
+            Some(repeat) => usize::try_from(repeat.pass)
+                .ok()
+                .and_then(|pass| pass.checked_mul(changes.len()))
+                .and_then(|rows| rows.checked_add(repeat.index + 1))
+                .unwrap_or(usize::MAX),
+            None => changes.len(),
+        },
+    };
+
+    if options.format == TraceFormat::Csv {
+        writeln!(
+            out,
+            "pass,index,change,frequency,first_seen_pass,first_seen_index,first_repeat"
+        )?;
+    }
+
+    let mut first_seen: HashMap<F, Option<Step>> = HashMap::from([(F::ZERO, None)]);
+    let mut repeated = false;
+    let mut curr_sum = F::ZERO;
+    let mut rows = 0;
+    let mut pass = 0;
+
+    while rows < max_rows {
+        for (idx, &chg) in changes.iter().enumerate().take(max_rows - rows) {
+            curr_sum = curr_sum
+                .checked_add(chg)
+                .ok_or(TraceError::Overflow(Overflow {
+                    pass,
+                    line: idx + 1,
+                }))?;
+
+            let step = Step { pass, index: idx };
+            let seen_at = *first_seen.entry(curr_sum).or_insert(Some(step));
+            let is_first_repeat = !repeated && seen_at != Some(step);
+            repeated |= is_first_repeat;
+
+            write_row(
+                out,
+                options.format,
+                &TraceRow {
+                    pass,
+                    index: idx,
+                    change: chg,
+                    frequency: curr_sum,
+                    first_seen_at: seen_at,
+                    first_repeat: is_first_repeat,
+                },
+            )?;
+            rows += 1;
+        }
+
+        pass += 1;
+    }
+
+    Ok(rows)
+}
+
+fn write_row<F: Frequency, W: Write>(
+    out: &mut W,
+    format: TraceFormat,
+    row: &TraceRow<F>,
+) -> io::Result<()> {
+    match format {
+        TraceFormat::Csv => {
+            let (seen_pass, seen_index) = match row.first_seen_at {
+                Some(step) => (step.pass.to_string(), step.index.to_string()),
+                None => (String::new(), String::new()),
+            };
+
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{}",
+                row.pass,
+                row.index,
+                row.change,
+                row.frequency,
+                seen_pass,
+                seen_index,
+                row.first_repeat
+            )
+        }
+        TraceFormat::JsonLines => {
+            let seen = match row.first_seen_at {
+                Some(step) => format!("{{\"pass\":{},\"index\":{}}}", step.pass, step.index),
+                None => "null".to_owned(),
+            };
+
+            writeln!(
+                out,
+                "{{\"pass\":{},\"index\":{},\"change\":{},\"frequency\":{},\"first_seen_at\":{},\"first_repeat\":{}}}",
+                row.pass, row.index, row.change, row.frequency, seen, row.first_repeat
+            )
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::checksum::letter_counts;
+use std::collections::HashMap;
+
+/// IDs (by index, ascending) made of exactly the same letters.
+///
+/// `signature` is the shared letter multiset, written as the letters in
+/// sorted order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnagramGroup {
+    pub signature: String,
+    pub members: Vec<usize>,
+}
+
+/// Groups IDs by letter multiset and returns every group with more than one
+/// member, largest first (ties broken by signature).
+///
+/// Exact duplicates share a multiset too, so they are grouped like any
+/// other anagram.
+pub fn anagram_groups(ids: &[String]) -> Vec<AnagramGroup> {
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for (idx, id) in ids.iter().enumerate() {
+        groups.entry(signature(id)).or_default().push(idx);
+    }
+
+    let mut groups: Vec<AnagramGroup> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(signature, members)| AnagramGroup { signature, members })
+        .collect();
+
+    groups.sort_unstable_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then_with(|| a.signature.cmp(&b.signature))
+    });
+    groups
+}
+
+fn signature(id: &str) -> String {
+    let mut counts: Vec<(char, usize)> = letter_counts(id).into_iter().collect();
+    counts.sort_unstable();
+
+    counts
+        .into_iter()
+        .flat_map(|(chr, count)| std::iter::repeat_n(chr, count))
+        .collect()
+}
@@ -0,0 +1,47 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// How many IDs contain some letter exactly `n` times, for each requested
+/// `n`, and the product of those counts (saturating at `u128::MAX`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub counts: BTreeMap<usize, usize>,
+    pub product: u128,
+}
+
+/// Number of times each character appears in `id`.
+pub fn letter_counts(id: &str) -> HashMap<char, usize> {
+    let mut char_counts: HashMap<char, usize> = HashMap::new();
+
+    for chr in id.chars() {
+        *char_counts.entry(chr).or_insert(0) += 1;
+    }
+
+    char_counts
+}
+
+/*  Generalised part 1 checksum.
+
+An ID counts once towards `n` if any of its letters appears exactly `n`
+times, however many letters do. The puzzle's checksum is
+`checksum(ids, &[2, 3]).product`.*/
+pub fn checksum(ids: &[String], multiplicities: &[usize]) -> Checksum {
+    let mut counts: BTreeMap<usize, usize> = multiplicities.iter().map(|&n| (n, 0)).collect();
+
+    for id in ids {
+        let mut found: Vec<usize> = letter_counts(id).into_values().collect();
+        found.sort_unstable();
+        found.dedup();
+
+        for n in found {
+            if let Some(count) = counts.get_mut(&n) {
+                *count += 1;
+            }
+        }
+    }
+
+    let product = counts.values().fold(1u128, |product, &count| {
+        product.saturating_mul(count as u128)
+    });
+
+    Checksum { counts, product }
+}
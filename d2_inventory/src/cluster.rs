@@ -0,0 +1,130 @@
+use crate::one_off::one_off_pairs;
+use std::collections::HashMap;
+
+/// IDs (by index, ascending) connected through one-character differences.
+///
+/// `mask` keeps the letters all members agree on and has `_` where they
+/// don't. Members are always the same length, since only same-length IDs
+/// can differ in one position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    pub members: Vec<usize>,
+    pub mask: String,
+}
+
+impl Cluster {
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+/// Groups IDs into the connected components of the one-off graph.
+///
+/// Every ID ends up in exactly one cluster, so IDs with no neighbours form
+/// clusters of their own. Largest clusters come first.
+pub fn clusters(ids: &[String]) -> Vec<Cluster> {
+    let mut parents: Vec<usize> = (0..ids.len()).collect();
+
+    for pair in one_off_pairs(ids) {
+        let a = find_root(&mut parents, pair.first);
+        let b = find_root(&mut parents, pair.second);
+        parents[a.max(b)] = a.min(b);
+    }
+
+    let mut components: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in 0..ids.len() {
+        let root = find_root(&mut parents, idx);
+        components.entry(root).or_default().push(idx);
+    }
+
+    let mut clusters: Vec<Cluster> = components
+        .into_values()
+        .map(|members| Cluster {
+            mask: common_mask(ids, &members),
+            members,
+        })
+        .collect();
+
+    clusters.sort_unstable_by(|a, b| {
+        b.size()
+            .cmp(&a.size())
+            .then(a.members[0].cmp(&b.members[0]))
+    });
+    clusters
+}
+
+/*  Clusters as a JSON array, largest first:
+
+[{"size":2,"mask":"ab_de","members":[{"index":0,"id":"abcde"},...]},...]*/
+pub fn clusters_to_json(clusters: &[Cluster], ids: &[String]) -> String {
+    let entries: Vec<String> = clusters
+        .iter()
+        .map(|cluster| {
+            let members: Vec<String> = cluster
+                .members
+                .iter()
+                .map(|&idx| format!("{{\"index\":{},\"id\":{}}}", idx, json_string(&ids[idx])))
+                .collect();
+
+            format!(
+                "{{\"size\":{},\"mask\":{},\"members\":[{}]}}",
+                cluster.size(),
+                json_string(&cluster.mask),
+                members.join(",")
+            )
+        })
+        .collect();
+
+    format!("[{}]", entries.join(","))
+}
+
+fn find_root(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    // Point everything on the path straight at the root
+    let mut curr = idx;
+    while parents[curr] != root {
+        let next = parents[curr];
+        parents[curr] = root;
+        curr = next;
+    }
+
+    root
+}
+
+fn common_mask(ids: &[String], members: &[usize]) -> String {
+    let mut mask: Vec<char> = ids[members[0]].chars().collect();
+
+    for &idx in &members[1..] {
+        for (slot, chr) in mask.iter_mut().zip(ids[idx].chars()) {
+            if *slot != chr {
+                *slot = '_';
+            }
+        }
+    }
+
+    mask.into_iter().collect()
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+
+    for chr in text.chars() {
+        match chr {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            chr if (chr as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", chr as u32)),
+            chr => escaped.push(chr),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,371 @@
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Substitutions only; IDs of different lengths never match.
+    Hamming,
+    /// Substitutions, insertions and deletions.
+    Levenshtein,
+}
+
+/// Two IDs (by index, `first < second`) within the searched distance.
+///
+/// `common` is what's left of either ID once the differences are taken
+/// out. `first_positions` and `second_positions` are the indices in each ID
+/// that aren't part of `common`; for Hamming they're the same list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub first: usize,
+    pub second: usize,
+    pub distance: usize,
+    pub common: String,
+    pub first_positions: Vec<usize>,
+    pub second_positions: Vec<usize>,
+}
+
+/*  Finds every pair of IDs at most `k` apart under `metric`.
+
+Hamming: if two IDs differ in at most `k` places, then splitting the
+positions into `k + 1` blocks leaves at least one block where they agree
+exactly. So IDs are bucketed by each block's contents and only IDs sharing
+a bucket get compared.
+
+Levenshtein: there's no bucketing, so this is quadratic in the number of
+IDs. IDs are sorted by length and each one is compared with those at most
+`k` characters longer, using a DP limited to a band of width `2k + 1`
+that gives up as soon as the band exceeds `k`. The same band gives the
+alignment of the pairs that match.
+
+Matches come out sorted by (first, second).*/
+pub fn within_distance(ids: &[String], metric: Metric, k: usize) -> Vec<Match> {
+    let chars: Vec<Vec<char>> = ids.iter().map(|id| id.chars().collect()).collect();
+
+    let mut matches: Vec<Match> = match metric {
+        Metric::Hamming => hamming_candidates(&chars, k)
+            .into_iter()
+            .filter_map(|(first, second)| hamming_match(&chars, first, second, k))
+            .collect(),
+        Metric::Levenshtein => levenshtein_matches(&chars, k),
+    };
+
+    matches.sort_unstable_by_key(|m| (m.first, m.second));
+    matches
+}
+
+fn hamming_candidates(chars: &[Vec<char>], k: usize) -> HashSet<(usize, usize)> {
+    let mut by_len: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (idx, id) in chars.iter().enumerate() {
+        by_len.entry(id.len()).or_default().push(idx);
+    }
+
+    let mut candidates: HashSet<(usize, usize)> = HashSet::new();
+
+    for (len, members) in by_len {
+        // Block `b` covers positions [bounds[b], bounds[b + 1]). With `k`
+        // at least the length, a single empty block makes every pair a
+        // candidate.
+        let bounds: Vec<usize> = if k < len {
+            (0..=k + 1).map(|b| b * len / (k + 1)).collect()
+        } else {
+            vec![0, 0]
+        };
+
+        for block in bounds.windows(2) {
+            let mut buckets: HashMap<&[char], Vec<usize>> = HashMap::new();
+
+            for &idx in &members {
+                buckets
+                    .entry(&chars[idx][block[0]..block[1]])
+                    .or_default()
+                    .push(idx);
+            }
+
+            for bucket in buckets.values() {
+                for (i, &first) in bucket.iter().enumerate() {
+                    for &second in &bucket[i + 1..] {
+                        candidates.insert((first.min(second), first.max(second)));
+                    }
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+fn hamming_match(chars: &[Vec<char>], first: usize, second: usize, k: usize) -> Option<Match> {
+    let (a, b) = (&chars[first], &chars[second]);
+    let positions: Vec<usize> = (0..a.len()).filter(|&idx| a[idx] != b[idx]).collect();
+
+    if positions.len() > k {
+        return None;
+    }
+
+    let common = a
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _)| a[idx] == b[idx])
+        .map(|(_, &chr)| chr)
+        .collect();
+
+    Some(Match {
+        first,
+        second,
+        distance: positions.len(),
+        common,
+        first_positions: positions.clone(),
+        second_positions: positions,
+    })
+}
+
+// Checks each candidate pair as soon as it's found, without collecting them
+fn levenshtein_matches(chars: &[Vec<char>], k: usize) -> Vec<Match> {
+    let mut order: Vec<usize> = (0..chars.len()).collect();
+    order.sort_by_key(|&idx| chars[idx].len());
+
+    let mut matches: Vec<Match> = Vec::new();
+
+    for (pos, &first) in order.iter().enumerate() {
+        for &second in &order[pos + 1..] {
+            if chars[second].len() - chars[first].len() > k {
+                break;
+            }
+
+            matches.extend(levenshtein_match(
+                chars,
+                first.min(second),
+                first.max(second),
+                k,
+            ));
+        }
+    }
+
+    matches
+}
+
+fn levenshtein_match(chars: &[Vec<char>], first: usize, second: usize, k: usize) -> Option<Match> {
+    let (a, b) = (&chars[first], &chars[second]);
+    let band = Band::new(a, b, k)?;
+    let distance = band.get(a.len(), b.len());
+
+    // Trace back through the band to recover the alignment. Every cell on
+    // an alignment costing at most `k` lies within it.
+    let (mut i, mut j) = (a.len(), b.len());
+    let mut common: Vec<char> = Vec::new();
+    let mut first_positions: Vec<usize> = Vec::new();
+    let mut second_positions: Vec<usize> = Vec::new();
+
+    while i > 0 || j > 0 {
+        let cost = band.get(i, j);
+
+        if i > 0 && j > 0 && a[i - 1] == b[j - 1] && cost == band.get(i - 1, j - 1) {
+            common.push(a[i - 1]);
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && cost == band.get(i - 1, j - 1) + 1 {
+            first_positions.push(i - 1);
+            second_positions.push(j - 1);
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && cost == band.get(i - 1, j) + 1 {
+            first_positions.push(i - 1);
+            i -= 1;
+        } else {
+            second_positions.push(j - 1);
+            j -= 1;
+        }
+    }
+
+    common.reverse();
+    first_positions.reverse();
+    second_positions.reverse();
+
+    Some(Match {
+        first,
+        second,
+        distance,
+        common: common.into_iter().collect(),
+        first_positions,
+        second_positions,
+    })
+}
+
+// Edit distance table limited to cells within `k` of the diagonal. Costs
+// over `k` are stored as `k + 1`.
+struct Band {
+    k: usize,
+    rows: Vec<Vec<usize>>,
+}
+
+impl Band {
+    // `None` if the edit distance is over `k`, giving up as soon as every
+    // cell in a row is over `k`
+    fn new(a: &[char], b: &[char], k: usize) -> Option<Self> {
+        if a.len().abs_diff(b.len()) > k {
+            return None;
+        }
+
+        // No edit distance is over the longer length, so a wider band
+        // wouldn't change anything
+        let k = k.min(a.len().max(b.len()));
+        let over = k + 1;
+        let mut band = Band {
+            k,
+            rows: Vec::with_capacity(a.len() + 1),
+        };
+
+        band.rows.push(
+            (0..=2 * k)
+                .map(|offset| match offset.checked_sub(k) {
+                    Some(j) if j <= b.len() => j.min(over),
+                    _ => over,
+                })
+                .collect(),
+        );
+
+        for i in 1..=a.len() {
+            band.rows.push(vec![over; 2 * k + 1]);
+
+            let lo = i.saturating_sub(k);
+            let hi = (i + k).min(b.len());
+
+            for j in lo..=hi {
+                let cost = if j == 0 {
+                    i.min(over)
+                } else {
+                    let substitute = band.get(i - 1, j - 1) + usize::from(a[i - 1] != b[j - 1]);
+                    substitute
+                        .min(band.get(i - 1, j) + 1)
+                        .min(band.get(i, j - 1) + 1)
+                        .min(over)
+                };
+
+                band.rows[i][j + k - i] = cost;
+            }
+
+            if band.rows[i].iter().all(|&cost| cost > k) {
+                return None;
+            }
+        }
+
+        Some(band).filter(|band| band.get(a.len(), b.len()) <= k)
+    }
+
+    fn get(&self, i: usize, j: usize) -> usize {
+        if j + self.k < i || j > i + self.k {
+            self.k + 1
+        } else {
+            self.rows[i][j + self.k - i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::distance::{within_distance, Match, Metric};
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    // `common` is each ID with its positions taken out
+    fn check_consistent(ids: &[String], m: &Match) {
+        for (idx, positions) in [
+            (m.first, &m.first_positions),
+            (m.second, &m.second_positions),
+        ] {
+            let rest: String = ids[idx]
+                .chars()
+                .enumerate()
+                .filter(|(pos, _)| !positions.contains(pos))
+                .map(|(_, chr)| chr)
+                .collect();
+
+            assert_eq!(m.common, rest, "{:?}", m);
+        }
+    }
+
+    #[test]
+    fn test_hamming() {
+        let ids = ids(&["abcde", "axcye", "abcdf", "abcd", "abcde"]);
+        let matches = within_distance(&ids, Metric::Hamming, 1);
+
+        let pairs: Vec<(usize, usize, usize)> = matches
+            .iter()
+            .map(|m| (m.first, m.second, m.distance))
+            .collect();
+        assert_eq!(vec![(0, 2, 1), (0, 4, 0), (2, 4, 1)], pairs);
+        assert_eq!("abcd", matches[0].common);
+        assert_eq!(vec![4], matches[0].first_positions);
+
+        for m in within_distance(&ids, Metric::Hamming, 2) {
+            assert_eq!(m.first_positions, m.second_positions);
+            check_consistent(&ids, &m);
+        }
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        let ids = ids(&["kitten", "sitting", "kitchen", "sittin", "mitten"]);
+        let matches = within_distance(&ids, Metric::Levenshtein, 3);
+
+        let pairs: Vec<(usize, usize, usize)> = matches
+            .iter()
+            .map(|m| (m.first, m.second, m.distance))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, 1, 3),
+                (0, 2, 2),
+                (0, 3, 2),
+                (0, 4, 1),
+                (1, 3, 1),
+                (1, 4, 3),
+                (2, 4, 3),
+                (3, 4, 2)
+            ],
+            pairs
+        );
+
+        // "sitting" is "sittin" plus a trailing "g"
+        assert_eq!("sittin", matches[4].common);
+        assert_eq!(vec![6], matches[4].first_positions);
+        assert!(matches[4].second_positions.is_empty());
+
+        for m in &matches {
+            check_consistent(&ids, m);
+        }
+    }
+
+    #[test]
+    fn test_zero_distance() {
+        let ids = ids(&["abc", "abd", "abc", "ab"]);
+
+        for metric in [Metric::Hamming, Metric::Levenshtein] {
+            let matches = within_distance(&ids, metric, 0);
+
+            assert_eq!(1, matches.len());
+            assert_eq!(
+                (0, 2, 0),
+                (matches[0].first, matches[0].second, matches[0].distance)
+            );
+            assert_eq!("abc", matches[0].common);
+        }
+    }
+
+    #[test]
+    fn test_huge_distance() {
+        let ids = ids(&["ab", "xyz", ""]);
+
+        for metric in [Metric::Hamming, Metric::Levenshtein] {
+            for m in within_distance(&ids, metric, usize::MAX) {
+                check_consistent(&ids, &m);
+            }
+        }
+
+        assert_eq!(
+            3,
+            within_distance(&ids, Metric::Levenshtein, usize::MAX).len()
+        );
+    }
+}
@@ -0,0 +1,17 @@
+pub mod anagram;
+pub mod checksum;
+pub mod cluster;
+pub mod distance;
+pub mod one_off;
+pub mod trie;
+pub mod validate;
+
+pub use anagram::{anagram_groups, AnagramGroup};
+pub use checksum::{checksum, letter_counts, Checksum};
+pub use cluster::{clusters, clusters_to_json, Cluster};
+pub use distance::{within_distance, Match, Metric};
+pub use one_off::{one_off_pairs, OneOffPair};
+pub use trie::{IdTrie, PatternError};
+pub use validate::{
+    validate, validated_ids, Issue, IssueKind, SearchMode, ValidatedIds, ValidationReport,
+};
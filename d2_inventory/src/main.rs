@@ -1,42 +1,113 @@
+use day2_inventory::{
+    anagram_groups, checksum, clusters, clusters_to_json, one_off_pairs, validated_ids, Cluster,
+    IdTrie, SearchMode, ValidatedIds,
+};
 use std::{
-    fs,
+    env, fs,
     io::{BufRead, BufReader},
+    process,
 };
 
 fn main() {
-    let input = get_input_as_vec();
-    let mut correct_id: Vec<char> = Vec::new();
+    // Usage: day2_inventory [--strict] [--clusters | --anagrams | --query PATTERN...]
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mode = if args.iter().any(|arg| arg == "--strict") {
+        SearchMode::Strict
+    } else {
+        SearchMode::Lenient
+    };
 
-    // Find matching IDs
-    for (i, this_id) in input.iter().enumerate() {
-        for (j, other_id) in input.iter().enumerate() {
-            if i == j {
-                continue;
-            }
+    let input = get_input_as_vec();
 
-            let matching_chars: Vec<char> = this_id
-                .chars()
-                .zip(other_id.chars())
-                .filter(|(a, b)| a == b)
-                .map(|(a, _)| a)
+    if args.iter().any(|arg| arg == "--anagrams") {
+        for group in anagram_groups(&input) {
+            let members: Vec<&str> = group
+                .members
+                .iter()
+                .map(|&idx| input[idx].as_str())
                 .collect();
+            println!(
+                "{} ({}): {}",
+                group.signature,
+                members.len(),
+                members.join(" ")
+            );
+        }
+        return;
+    }
 
-            if this_id.len() == matching_chars.len() + 1 {
-                correct_id = matching_chars;
-                break;
+    if args.iter().any(|arg| arg == "--clusters") {
+        let validated = search_ids(&input, mode);
+
+        // Point members back at input lines rather than validated IDs
+        let found: Vec<Cluster> = clusters(&validated.ids)
+            .into_iter()
+            .map(|cluster| Cluster {
+                members: cluster
+                    .members
+                    .iter()
+                    .map(|&idx| validated.lines[idx] - 1)
+                    .collect(),
+                mask: cluster.mask,
+            })
+            .collect();
+
+        println!("{}", clusters_to_json(&found, &input));
+        return;
+    }
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--query") {
+        let validated = search_ids(&input, mode);
+        let trie = IdTrie::from_ids(&validated.ids);
+
+        for pattern in &args[idx + 1..] {
+            match trie.matches(pattern) {
+                Ok(found) => {
+                    println!("{} ({}):", pattern, found.len());
+                    for idx in found {
+                        println!("  {}", validated.ids[idx]);
+                    }
+                }
+                Err(err) => eprintln!("{}: {}", pattern, err),
             }
         }
+        return;
+    }
 
-        if correct_id.len() > 0 {
-            break;
-        }
+    // Count IDs with a letter appearing exactly 2 or 3 times
+    println!("Part 1: {}", checksum(&input, &[2, 3]).product);
+
+    // Find matching IDs
+    let validated = search_ids(&input, mode);
+    let pairs = one_off_pairs(&validated.ids);
+
+    // Print answer to console
+    match pairs.first() {
+        Some(pair) => println!("Part 2: {}", pair.common(&validated.ids)),
+        None => println!("No two IDs differ by exactly one character"),
     }
 
-    // Print asnwer to console
-    for chr in correct_id {
-        print!("{}", chr);
+    if pairs.len() > 1 {
+        println!("({} matching pairs in total)", pairs.len());
+    }
+}
+
+// Checks IDs before searching them, skipping bad ones unless strict
+fn search_ids(input: &[String], mode: SearchMode) -> ValidatedIds {
+    match validated_ids(input, mode) {
+        Ok(validated) => {
+            for issue in &validated.report.issues {
+                eprintln!("Skipped {}", issue);
+            }
+            validated
+        }
+        Err(report) => {
+            for issue in &report.issues {
+                eprintln!("{}", issue);
+            }
+            process::exit(1);
+        }
     }
-    println!();
 }
 
 fn get_input_as_vec() -> Vec<String> {
@@ -54,36 +125,3 @@ fn get_input_as_vec() -> Vec<String> {
 
     input_as_vec
 }
-
-/*  Part 1:
-
-   let input = get_input_as_vec();
-   let mut two_count = 0;
-   let mut three_count = 0;
-
-   for id in input {
-       let mut char_counts: HashMap<char, i32> = HashMap::new();
-
-       for chr in id.chars() {
-           let curr_count = char_counts.entry(chr).or_insert(0);
-           *curr_count += 1;
-       }
-
-       let mut found2 = false;
-       let mut found3 = false;
-       for count in char_counts.values() {
-           if found2 && found3 { break; }
-
-           match count {
-               2 => found2 = true,
-               3 => found3 = true,
-               _ => continue
-           }
-       }
-
-       if found2 { two_count += 1; }
-       if found3 { three_count += 1; }
-   }
-
-   println!("{}", two_count * three_count);
-*/
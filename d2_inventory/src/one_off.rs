@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+/// Two IDs (by index) of equal length that differ at exactly `position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OneOffPair {
+    pub first: usize,
+    pub second: usize,
+    pub position: usize,
+}
+
+impl OneOffPair {
+    /// The letters both IDs share, i.e. either ID without `position`.
+    pub fn common(&self, ids: &[String]) -> String {
+        ids[self.first]
+            .chars()
+            .enumerate()
+            .filter(|&(idx, _)| idx != self.position)
+            .map(|(_, chr)| chr)
+            .collect()
+    }
+}
+
+/*  Finds every pair of IDs that differ in exactly one position.
+
+Two IDs of the same length differ only at position `p` iff they're
+identical once `p` is deleted from both. So for each position, IDs are
+bucketed by a hash of "the ID minus that position", which prefix and
+suffix hashes give in O(1) per position, and only IDs in the same bucket
+get compared. Copies of the same ID are searched once and the pairs found
+are repeated for every copy, so that's O(n * L) work plus the size of the
+output.
+
+Pairs come out sorted, with `first < second`. Identical IDs are not
+reported, since they don't differ anywhere.*/
+pub fn one_off_pairs(ids: &[String]) -> Vec<OneOffPair> {
+    // Indices of every copy of each distinct ID, in order of first appearance
+    let mut copies: Vec<Vec<usize>> = Vec::new();
+    let mut distinct: HashMap<&str, usize> = HashMap::new();
+    for (idx, id) in ids.iter().enumerate() {
+        let next = copies.len();
+        let slot = *distinct.entry(id.as_str()).or_insert(next);
+        if slot == next {
+            copies.push(Vec::new());
+        }
+        copies[slot].push(idx);
+    }
+
+    let chars: Vec<Vec<char>> = copies
+        .iter()
+        .map(|indices| ids[indices[0]].chars().collect())
+        .collect();
+
+    // Only IDs of the same length can differ in exactly one position
+    let mut by_len: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (idx, id) in chars.iter().enumerate() {
+        by_len.entry(id.len()).or_default().push(idx);
+    }
+
+    let mut pairs: Vec<OneOffPair> = Vec::new();
+
+    for (len, members) in by_len {
+        if members.len() < 2 {
+            continue;
+        }
+
+        let hashes: Vec<PrefixHash> = members
+            .iter()
+            .map(|&idx| PrefixHash::new(&chars[idx]))
+            .collect();
+
+        for position in 0..len {
+            let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+
+            for (member, hash) in members.iter().zip(&hashes) {
+                buckets
+                    .entry(hash.without(position))
+                    .or_default()
+                    .push(*member);
+            }
+
+            for bucket in buckets.values().filter(|bucket| bucket.len() > 1) {
+                for (i, &first) in bucket.iter().enumerate() {
+                    for &second in &bucket[i + 1..] {
+                        if !differs_only_at(&chars[first], &chars[second], position) {
+                            continue;
+                        }
+
+                        for &a in &copies[first] {
+                            for &b in &copies[second] {
+                                pairs.push(OneOffPair {
+                                    first: a.min(b),
+                                    second: a.max(b),
+                                    position,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+// Rules out hash collisions
+fn differs_only_at(a: &[char], b: &[char], position: usize) -> bool {
+    a[position] != b[position]
+        && a[..position] == b[..position]
+        && a[position + 1..] == b[position + 1..]
+}
+
+// Polynomial hashes of every prefix and suffix, wrapping mod 2^64
+struct PrefixHash {
+    prefix: Vec<u64>,
+    suffix: Vec<u64>,
+    powers: Vec<u64>,
+}
+
+const BASE: u64 = 0x100000001b3;
+
+impl PrefixHash {
+    fn new(id: &[char]) -> Self {
+        let len = id.len();
+        let mut prefix = vec![0u64; len + 1];
+        let mut suffix = vec![0u64; len + 1];
+        let mut powers = vec![1u64; len + 1];
+
+        for idx in 0..len {
+            prefix[idx + 1] = prefix[idx]
+                .wrapping_mul(BASE)
+                .wrapping_add(id[idx] as u64 + 1);
+            powers[idx + 1] = powers[idx].wrapping_mul(BASE);
+        }
+
+        for idx in (0..len).rev() {
+            suffix[idx] = suffix[idx + 1]
+                .wrapping_mul(BASE)
+                .wrapping_add(id[idx] as u64 + 1);
+        }
+
+        PrefixHash {
+            prefix,
+            suffix,
+            powers,
+        }
+    }
+
+    // Hash of the ID with `position` deleted
+    fn without(&self, position: usize) -> u64 {
+        let tail_len = self.prefix.len() - 1 - (position + 1);
+
+        self.prefix[position]
+            .wrapping_mul(self.powers[tail_len])
+            .wrapping_add(self.suffix[position + 1])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::one_off::{one_off_pairs, OneOffPair};
+
+    #[test]
+    fn test_one_off_pairs() {
+        let ids: Vec<String> = [
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ]
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+
+        let pairs = one_off_pairs(&ids);
+
+        assert_eq!(
+            vec![OneOffPair {
+                first: 1,
+                second: 4,
+                position: 2
+            }],
+            pairs
+        );
+        assert_eq!("fgij", pairs[0].common(&ids));
+    }
+
+    #[test]
+    fn test_duplicate_ids() {
+        let ids: Vec<String> = ["abc", "abd", "abc", "abc", "xbd"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+
+        let pairs: Vec<(usize, usize, usize)> = one_off_pairs(&ids)
+            .iter()
+            .map(|pair| (pair.first, pair.second, pair.position))
+            .collect();
+
+        assert_eq!(vec![(0, 1, 2), (1, 2, 2), (1, 3, 2), (1, 4, 0)], pairs);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{collections::BTreeMap, error::Error, fmt};
+
+/// A `*` somewhere other than the end of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternError {
+    pub position: usize,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`*` at position {} is only allowed at the end of a pattern",
+            self.position
+        )
+    }
+}
+
+impl Error for PatternError {}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: BTreeMap<char, usize>,
+    // IDs passing through (or ending at) this node
+    count: usize,
+    // Indices of the IDs ending here; more than one for duplicates
+    ids: Vec<usize>,
+}
+
+/*  Prefix tree over box IDs for wildcard lookups.
+
+Patterns are matched against whole IDs: `?` stands for exactly one
+character and a trailing `*` for any (possibly empty) rest of the ID, so
+`ab?de*` matches `abxde` and `abxdefg` but not `abde`.*/
+#[derive(Debug)]
+pub struct IdTrie {
+    nodes: Vec<Node>,
+}
+
+impl Default for IdTrie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdTrie {
+    pub fn new() -> Self {
+        IdTrie {
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// Builds a trie where each ID is known by its index in `ids`.
+    pub fn from_ids(ids: &[String]) -> Self {
+        let mut trie = IdTrie::new();
+
+        for (idx, id) in ids.iter().enumerate() {
+            trie.insert(id, idx);
+        }
+
+        trie
+    }
+
+    pub fn insert(&mut self, id: &str, index: usize) {
+        let mut node = 0;
+        self.nodes[node].count += 1;
+
+        for chr in id.chars() {
+            node = match self.nodes[node].children.get(&chr) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(chr, child);
+                    child
+                }
+            };
+            self.nodes[node].count += 1;
+        }
+
+        self.nodes[node].ids.push(index);
+    }
+
+    /// Number of IDs inserted, counting duplicates.
+    pub fn len(&self) -> usize {
+        self.nodes[0].count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.find(id)
+            .is_some_and(|node| !self.nodes[node].ids.is_empty())
+    }
+
+    /// Number of IDs starting with `prefix`, counting duplicates.
+    pub fn prefix_count(&self, prefix: &str) -> usize {
+        self.find(prefix).map_or(0, |node| self.nodes[node].count)
+    }
+
+    /// Indices of every ID matching `pattern`, in ascending order.
+    pub fn matches(&self, pattern: &str) -> Result<Vec<usize>, PatternError> {
+        let mut pattern: Vec<char> = pattern.chars().collect();
+
+        let open_ended = pattern.last() == Some(&'*');
+        if open_ended {
+            pattern.pop();
+        }
+
+        if let Some(position) = pattern.iter().position(|&chr| chr == '*') {
+            return Err(PatternError { position });
+        }
+
+        let mut found: Vec<usize> = Vec::new();
+        let mut frontier: Vec<(usize, usize)> = vec![(0, 0)];
+
+        while let Some((node, depth)) = frontier.pop() {
+            if depth == pattern.len() {
+                if open_ended {
+                    self.collect_subtree(node, &mut found);
+                } else {
+                    found.extend(&self.nodes[node].ids);
+                }
+                continue;
+            }
+
+            let children = &self.nodes[node].children;
+            match pattern[depth] {
+                '?' => frontier.extend(children.values().map(|&child| (child, depth + 1))),
+                chr => frontier.extend(children.get(&chr).map(|&child| (child, depth + 1))),
+            }
+        }
+
+        found.sort_unstable();
+        Ok(found)
+    }
+
+    fn find(&self, text: &str) -> Option<usize> {
+        text.chars()
+            .try_fold(0, |node, chr| self.nodes[node].children.get(&chr).copied())
+    }
+
+    fn collect_subtree(&self, root: usize, found: &mut Vec<usize>) {
+        let mut stack = vec![root];
+
+        while let Some(node) = stack.pop() {
+            found.extend(&self.nodes[node].ids);
+            stack.extend(self.nodes[node].children.values());
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Any issue stops the search.
+    Strict,
+    /// IDs with issues are left out of the search (later duplicates are
+    /// dropped, the first occurrence is kept).
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    Empty,
+    /// Length differs from the most common ID length.
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    /// A character other than `a-z`, at a 0-based char position.
+    InvalidChar {
+        position: usize,
+        chr: char,
+    },
+    /// Same ID as the one on `first_line`.
+    Duplicate {
+        first_line: usize,
+    },
+}
+
+/// A problem with the ID on `line` (1-based).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub line: usize,
+    pub id: String,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            IssueKind::Empty => write!(f, "empty line"),
+            IssueKind::WrongLength { expected, found } => write!(
+                f,
+                "{:?} has length {}, expected {}",
+                self.id, found, expected
+            ),
+            IssueKind::InvalidChar { position, chr } => write!(
+                f,
+                "{:?} has {:?} at position {}, expected a-z",
+                self.id, chr, position
+            ),
+            IssueKind::Duplicate { first_line } => {
+                write!(f, "{:?} duplicates line {}", self.id, first_line)
+            }
+        }
+    }
+}
+
+/// Every issue found, in line order. `expected_len` is the majority length
+/// among non-empty IDs (ties go to the shorter length).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub expected_len: Option<usize>,
+    pub issues: Vec<Issue>,
+}
+
+/// IDs that passed validation. `lines[i]` is the 1-based input line of
+/// `ids[i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatedIds {
+    pub ids: Vec<String>,
+    pub lines: Vec<usize>,
+    pub report: ValidationReport,
+}
+
+/// Checks every ID, one per input line, and reports all issues at once.
+pub fn validate(ids: &[String]) -> ValidationReport {
+    let mut len_counts: HashMap<usize, usize> = HashMap::new();
+    for id in ids.iter().filter(|id| !id.is_empty()) {
+        *len_counts.entry(id.chars().count()).or_insert(0) += 1;
+    }
+
+    let expected_len = len_counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(len, _)| len);
+
+    let mut first_seen: HashMap<&str, usize> = HashMap::new();
+    let mut issues: Vec<Issue> = Vec::new();
+
+    for (idx, id) in ids.iter().enumerate() {
+        let line = idx + 1;
+        let mut report = |kind: IssueKind| {
+            issues.push(Issue {
+                line,
+                id: id.clone(),
+                kind,
+            })
+        };
+
+        if id.is_empty() {
+            report(IssueKind::Empty);
+            continue;
+        }
+
+        let found = id.chars().count();
+        if let Some(expected) = expected_len.filter(|&expected| expected != found) {
+            report(IssueKind::WrongLength { expected, found });
+        }
+
+        for (position, chr) in id.chars().enumerate() {
+            if !chr.is_ascii_lowercase() {
+                report(IssueKind::InvalidChar { position, chr });
+            }
+        }
+
+        if let Some(&first_line) = first_seen.get(id.as_str()) {
+            report(IssueKind::Duplicate { first_line });
+        } else {
+            first_seen.insert(id, line);
+        }
+    }
+
+    ValidationReport {
+        expected_len,
+        issues,
+    }
+}
+
+/// Validates `ids` and keeps the ones safe to search.
+///
+/// In strict mode, any issue returns the report as an `Err`.
+pub fn validated_ids(ids: &[String], mode: SearchMode) -> Result<ValidatedIds, ValidationReport> {
+    let report = validate(ids);
+
+    if mode == SearchMode::Strict && !report.issues.is_empty() {
+        return Err(report);
+    }
+
+    let mut flagged = vec![false; ids.len()];
+    for issue in &report.issues {
+        flagged[issue.line - 1] = true;
+    }
+
+    let (lines, ids): (Vec<usize>, Vec<String>) = ids
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _)| !flagged[idx])
+        .map(|(idx, id)| (idx + 1, id.clone()))
+        .unzip();
+
+    Ok(ValidatedIds { ids, lines, report })
+}
@@ -0,0 +1,212 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// "#{id} @ {x},{y}: {w}x{h}": a `w` by `h` rectangle whose top-left
+/// square inch is at (`x`, `y`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Claim {
+    pub id: i32,
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+/// The parts of a claim, in the order they're written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Hash,
+    Id,
+    At,
+    X,
+    Comma,
+    Y,
+    Colon,
+    Width,
+    Times,
+    Height,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = match self {
+            Token::Hash => "`#`",
+            Token::Id => "claim ID",
+            Token::At => "`@`",
+            Token::X => "x coordinate",
+            Token::Comma => "`,`",
+            Token::Y => "y coordinate",
+            Token::Colon => "`:`",
+            Token::Width => "width",
+            Token::Times => "`x`",
+            Token::Height => "height",
+            Token::End => "end of line",
+        };
+
+        write!(f, "{}", token)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimErrorKind {
+    /// Something other than `token` was found.
+    Expected(Token),
+    /// The number for `token` doesn't fit an `i32`.
+    OutOfRange(Token),
+}
+
+/// Why a claim failed to parse. `column` is the 1-based character the
+/// problem starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimError {
+    pub column: usize,
+    pub kind: ClaimErrorKind,
+}
+
+impl fmt::Display for ClaimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ClaimErrorKind::Expected(token) => {
+                write!(f, "column {}: expected {}", self.column, token)
+            }
+            ClaimErrorKind::OutOfRange(token) => {
+                write!(f, "column {}: {} out of range", self.column, token)
+            }
+        }
+    }
+}
+
+impl Error for ClaimError {}
+
+/*  Parses "#{id} @ {x},{y}: {w}x{h}".
+
+Any amount of whitespace (including none) is allowed between the parts and
+around the claim. Numbers are unsigned runs of digits.*/
+impl FromStr for Claim {
+    type Err = ClaimError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor { text: s, pos: 0 };
+
+        cursor.symbol('#', Token::Hash)?;
+        let id = cursor.number(Token::Id)?;
+        cursor.symbol('@', Token::At)?;
+        let x = cursor.number(Token::X)?;
+        cursor.symbol(',', Token::Comma)?;
+        let y = cursor.number(Token::Y)?;
+        cursor.symbol(':', Token::Colon)?;
+        let w = cursor.number(Token::Width)?;
+        cursor.symbol('x', Token::Times)?;
+        let h = cursor.number(Token::Height)?;
+
+        cursor.skip_whitespace();
+        if cursor.pos < s.len() {
+            return Err(cursor.error(ClaimErrorKind::Expected(Token::End)));
+        }
+
+        Ok(Claim { id, x, y, w, h })
+    }
+}
+
+// Position (in bytes) within the text being parsed
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn symbol(&mut self, symbol: char, token: Token) -> Result<(), ClaimError> {
+        self.skip_whitespace();
+
+        if !self.rest().starts_with(symbol) {
+            return Err(self.error(ClaimErrorKind::Expected(token)));
+        }
+
+        self.pos += symbol.len_utf8();
+        Ok(())
+    }
+
+    fn number(&mut self, token: Token) -> Result<i32, ClaimError> {
+        self.skip_whitespace();
+
+        let rest = self.rest();
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+        if digits == 0 {
+            return Err(self.error(ClaimErrorKind::Expected(token)));
+        }
+
+        let number = rest[..digits]
+            .parse::<i32>()
+            .map_err(|_| self.error(ClaimErrorKind::OutOfRange(token)))?;
+
+        self.pos += digits;
+        Ok(number)
+    }
+
+    fn error(&self, kind: ClaimErrorKind) -> ClaimError {
+        ClaimError {
+            column: self.text[..self.pos].chars().count() + 1,
+            kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::claim::{Claim, ClaimError, ClaimErrorKind, Token};
+
+    #[test]
+    fn test_parse() {
+        let claim = Claim {
+            id: 123,
+            x: 3,
+            y: 2,
+            w: 5,
+            h: 4,
+        };
+
+        assert_eq!(Ok(claim), "#123 @ 3,2: 5x4".parse());
+        assert_eq!(Ok(claim), "  #123@3 , 2 :5 x 4 ".parse());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let tests = [
+            ("123 @ 3,2: 5x4", 1, ClaimErrorKind::Expected(Token::Hash)),
+            ("#@ 3,2: 5x4", 2, ClaimErrorKind::Expected(Token::Id)),
+            ("#3 @ 5,5 2x2", 10, ClaimErrorKind::Expected(Token::Colon)),
+            ("#1 @ 1,1: 2y2", 12, ClaimErrorKind::Expected(Token::Times)),
+            ("#1 @ 1,1: 2x", 13, ClaimErrorKind::Expected(Token::Height)),
+            (
+                "#1 @ 1,1: 2x2 junk",
+                15,
+                ClaimErrorKind::Expected(Token::End),
+            ),
+            (
+                "#5 @ 99999999999,1: 1x1",
+                6,
+                ClaimErrorKind::OutOfRange(Token::X),
+            ),
+            ("#é @ 1,1: 1x1", 2, ClaimErrorKind::Expected(Token::Id)),
+        ];
+
+        for (line, column, kind) in tests {
+            assert_eq!(
+                Err(ClaimError { column, kind }),
+                line.parse::<Claim>(),
+                "{}",
+                line
+            );
+        }
+    }
+}
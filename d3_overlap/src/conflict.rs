@@ -0,0 +1,119 @@
+use crate::claim::Claim;
+
+/// Another claim sharing `area` square inches with the one it's listed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    pub id: i32,
+    pub area: u64,
+}
+
+/// Which claims overlap which. `conflicts[i]` belongs to `ids[i]`, in input
+/// order, and is sorted by the other claim's position in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictGraph {
+    pub ids: Vec<i32>,
+    pub conflicts: Vec<Vec<Conflict>>,
+}
+
+impl ConflictGraph {
+    /*  Finds every overlapping pair of claims.
+
+    Claims are sorted by left edge, so each one is only compared with those
+    starting before its right edge.*/
+    pub fn from_claims(claims: &[Claim]) -> Self {
+        let mut order: Vec<usize> = (0..claims.len())
+            .filter(|&idx| claims[idx].w > 0 && claims[idx].h > 0)
+            .collect();
+        order.sort_by_key(|&idx| claims[idx].x);
+
+        let mut edges: Vec<Vec<(usize, u64)>> = vec![Vec::new(); claims.len()];
+
+        for (pos, &a) in order.iter().enumerate() {
+            let end_x = claims[a].x as i64 + claims[a].w as i64;
+
+            for &b in &order[pos + 1..] {
+                if claims[b].x as i64 >= end_x {
+                    break;
+                }
+
+                if let Some(area) = overlap_area(&claims[a], &claims[b]) {
+                    edges[a].push((b, area));
+                    edges[b].push((a, area));
+                }
+            }
+        }
+
+        let conflicts = edges
+            .into_iter()
+            .map(|mut list| {
+                list.sort_unstable();
+                list.into_iter()
+                    .map(|(idx, area)| Conflict {
+                        id: claims[idx].id,
+                        area,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        ConflictGraph {
+            ids: claims.iter().map(|claim| claim.id).collect(),
+            conflicts,
+        }
+    }
+
+    /*  One JSON object per claim, in input order:
+
+    [{"id":1,"conflicts":[{"id":3,"area":4}]},{"id":2,"conflicts":[]},...]*/
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .ids
+            .iter()
+            .zip(&self.conflicts)
+            .map(|(id, conflicts)| {
+                let list: Vec<String> = conflicts
+                    .iter()
+                    .map(|c| format!("{{\"id\":{},\"area\":{}}}", c.id, c.area))
+                    .collect();
+
+                format!("{{\"id\":{},\"conflicts\":[{}]}}", id, list.join(","))
+            })
+            .collect();
+
+        format!("[{}]", entries.join(","))
+    }
+
+    /// Undirected Graphviz graph with one node per claim and one edge per
+    /// overlapping pair, labelled with the shared area.
+    pub fn to_dot(&self) -> String {
+        let mut lines: Vec<String> = vec![String::from("graph claims {")];
+
+        for id in &self.ids {
+            lines.push(format!("    \"#{}\";", id));
+        }
+
+        for (id, conflicts) in self.ids.iter().zip(&self.conflicts) {
+            // Each pair is listed on both claims; only draw it once
+            for c in conflicts.iter().filter(|c| *id < c.id) {
+                lines.push(format!(
+                    "    \"#{}\" -- \"#{}\" [label=\"{}\"];",
+                    id, c.id, c.area
+                ));
+            }
+        }
+
+        lines.push(String::from("}"));
+        lines.join("\n")
+    }
+}
+
+// Square inches shared by two claims, if any
+fn overlap_area(a: &Claim, b: &Claim) -> Option<u64> {
+    let overlap = |start_a: i32, len_a: i32, start_b: i32, len_b: i32| {
+        let start = start_a.max(start_b) as i64;
+        let end = (start_a as i64 + len_a as i64).min(start_b as i64 + len_b as i64);
+        (end > start).then(|| (end - start) as u64)
+    };
+
+    Some(overlap(a.x, a.w, b.x, b.w)? * overlap(a.y, a.h, b.y, b.h)?)
+}
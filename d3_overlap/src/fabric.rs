@@ -0,0 +1,281 @@
+use crate::claim::Claim;
+use std::{error::Error, fmt};
+
+/// Largest bounding box `Fabric` will lay out, in square inches. Claims
+/// spread wider than that can still be handled by `sweep_overlaps`.
+pub const MAX_AREA: usize = 1 << 28;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FabricError {
+    /// A claim starts left of or above the fabric's edge.
+    NegativeCoordinate { id: i32 },
+    /// A claim has a negative width or height.
+    NegativeSize { id: i32 },
+    /// A claim's far edge doesn't fit in an `i32`.
+    Overflow { id: i32 },
+    /// The bounding box is over `MAX_AREA`, or can't be allocated.
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for FabricError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FabricError::NegativeCoordinate { id } => {
+                write!(f, "claim #{} has a negative coordinate", id)
+            }
+            FabricError::NegativeSize { id } => write!(f, "claim #{} has a negative size", id),
+            FabricError::Overflow { id } => {
+                write!(f, "claim #{} extends past the largest coordinate", id)
+            }
+            FabricError::TooLarge { width, height } => {
+                write!(f, "fabric of {}x{} inches is too large", width, height)
+            }
+        }
+    }
+}
+
+impl Error for FabricError {}
+
+/*  The claims, and the number of them on each square inch of fabric.
+
+Only the bounding box of the claims is stored, as a heap allocated
+`width * height` grid whose (0, 0) is the square inch at `origin`. Square
+inches outside of it have no claims.*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fabric {
+    claims: Vec<Claim>,
+    origin: (i32, i32),
+    width: usize,
+    height: usize,
+    counts: Vec<u32>,
+}
+
+impl Fabric {
+    /// Marks each square inch of fabric with the number of claims on it.
+    pub fn from_claims(claims: &[Claim]) -> Result<Self, FabricError> {
+        let mut bounds: Option<(i32, i32, i32, i32)> = None;
+
+        for claim in claims {
+            let (end_x, end_y) = claim_end(claim)?;
+
+            bounds = Some(match bounds {
+                Some((min_x, min_y, max_x, max_y)) => (
+                    min_x.min(claim.x),
+                    min_y.min(claim.y),
+                    max_x.max(end_x),
+                    max_y.max(end_y),
+                ),
+                None => (claim.x, claim.y, end_x, end_y),
+            });
+        }
+
+        let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((0, 0, 0, 0));
+        let width = (max_x - min_x) as usize;
+        let height = (max_y - min_y) as usize;
+
+        let too_large = FabricError::TooLarge { width, height };
+        let area = width
+            .checked_mul(height)
+            .filter(|&area| area <= MAX_AREA)
+            .ok_or(too_large)?;
+
+        let mut counts: Vec<u32> = Vec::new();
+        counts.try_reserve_exact(area).map_err(|_| too_large)?;
+        counts.resize(area, 0);
+
+        let mut fabric = Fabric {
+            claims: claims.to_vec(),
+            origin: (min_x, min_y),
+            width,
+            height,
+            counts,
+        };
+
+        for claim in claims {
+            for range in fabric.claim_rows(claim) {
+                for count in &mut fabric.counts[range] {
+                    *count += 1;
+                }
+            }
+        }
+
+        Ok(fabric)
+    }
+
+    pub fn claims(&self) -> &[Claim] {
+        &self.claims
+    }
+
+    /// Number of square inches within two or more claims.
+    pub fn overlap_area(&self) -> usize {
+        self.counts.iter().filter(|&&count| count > 1).count()
+    }
+
+    /// Every claim that shares no square inch with another, in input order.
+    pub fn intact_claims(&self) -> Vec<&Claim> {
+        self.claims
+            .iter()
+            .filter(|claim| self.overlapped_area(claim) == 0)
+            .collect()
+    }
+
+    /// Claims covering the square inch at (`x`, `y`), in input order.
+    pub fn claims_at(&self, x: i32, y: i32) -> Vec<&Claim> {
+        if self.count(x, y) == 0 {
+            return Vec::new();
+        }
+
+        self.claims
+            .iter()
+            .filter(|claim| {
+                (claim.x..claim.x + claim.w).contains(&x)
+                    && (claim.y..claim.y + claim.h).contains(&y)
+            })
+            .collect()
+    }
+
+    /// Fraction of each claim's area that's also claimed by others, as
+    /// (claim ID, ratio) in input order. Zero-area claims have a ratio of 0.
+    pub fn overlap_ratios(&self) -> Vec<(i32, f64)> {
+        self.claims
+            .iter()
+            .map(|claim| {
+                let area = claim.w as u64 * claim.h as u64;
+                let ratio = if area == 0 {
+                    0.0
+                } else {
+                    self.overlapped_area(claim) as f64 / area as f64
+                };
+
+                (claim.id, ratio)
+            })
+            .collect()
+    }
+
+    // Square inches of `claim` that are also claimed by others
+    fn overlapped_area(&self, claim: &Claim) -> usize {
+        self.claim_rows(claim)
+            .map(|range| {
+                self.counts[range]
+                    .iter()
+                    .filter(|&&count| count > 1)
+                    .count()
+            })
+            .sum()
+    }
+
+    // Ranges of `counts` covered by `claim`, one per row. Only valid for
+    // claims the fabric was built from.
+    fn claim_rows<'a>(
+        &self,
+        claim: &'a Claim,
+    ) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+        let (origin_x, origin_y) = self.origin;
+        let width = self.width;
+        let col = (claim.x - origin_x) as usize;
+        let top = (claim.y - origin_y) as usize;
+
+        (top..top + claim.h as usize).map(move |row| {
+            let start = row * width + col;
+            start..start + claim.w as usize
+        })
+    }
+
+    /// Top-left square inch covered by the grid.
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of claims on the square inch at (`x`, `y`).
+    pub fn count(&self, x: i32, y: i32) -> u32 {
+        self.index(x, y).map_or(0, |idx| self.counts[idx])
+    }
+
+    /// Claim counts along grid row `row`, i.e. `y = origin.1 + row`.
+    pub fn row(&self, row: usize) -> &[u32] {
+        let start = self.row_start(row);
+        &self.counts[start..start + self.width]
+    }
+
+    /// Largest number of claims on any one square inch.
+    pub fn max_count(&self) -> u32 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    fn row_start(&self, row: usize) -> usize {
+        row * self.width
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let col = usize::try_from(x as i64 - self.origin.0 as i64).ok()?;
+        let row = usize::try_from(y as i64 - self.origin.1 as i64).ok()?;
+
+        if col < self.width && row < self.height {
+            Some(self.row_start(row) + col)
+        } else {
+            None
+        }
+    }
+}
+
+// One past the bottom-right square inch of `claim`
+pub(crate) fn claim_end(claim: &Claim) -> Result<(i32, i32), FabricError> {
+    if claim.x < 0 || claim.y < 0 {
+        return Err(FabricError::NegativeCoordinate { id: claim.id });
+    }
+
+    if claim.w < 0 || claim.h < 0 {
+        return Err(FabricError::NegativeSize { id: claim.id });
+    }
+
+    let end_x = claim.x.checked_add(claim.w);
+    let end_y = claim.y.checked_add(claim.h);
+
+    end_x
+        .zip(end_y)
+        .ok_or(FabricError::Overflow { id: claim.id })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        claim::Claim,
+        fabric::{Fabric, FabricError},
+    };
+
+    #[test]
+    fn test_too_large() {
+        let claims = [
+            Claim {
+                id: 1,
+                x: 0,
+                y: 0,
+                w: 1,
+                h: 1,
+            },
+            Claim {
+                id: 2,
+                x: 99_999,
+                y: 99_999,
+                w: 1,
+                h: 1,
+            },
+        ];
+
+        assert_eq!(
+            Err(FabricError::TooLarge {
+                width: 100_000,
+                height: 100_000
+            }),
+            Fabric::from_claims(&claims)
+        );
+    }
+}
@@ -0,0 +1,19 @@
+pub mod claim;
+pub mod conflict;
+pub mod fabric;
+pub mod live;
+pub mod parse;
+pub mod placement;
+pub mod render;
+pub mod sweep;
+#[cfg(test)]
+mod testing;
+
+pub use claim::{Claim, ClaimError, ClaimErrorKind, Token};
+pub use conflict::{Conflict, ConflictGraph};
+pub use fabric::{Fabric, FabricError, MAX_AREA};
+pub use live::{LiveError, LiveFabric};
+pub use parse::{parse_claims, ParseError, ParseErrorKind, ParseMode, ParsedClaims};
+pub use placement::{first_free_position, free_positions};
+pub use render::{render_ascii, render_image, ImageFormat, RenderError, MAX_ASCII_WIDTH};
+pub use sweep::{sweep_overlaps, SweepSummary};
@@ -0,0 +1,249 @@
+use crate::{
+    claim::Claim,
+    fabric::{claim_end, FabricError},
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fmt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveError {
+    /// A claim with this ID is already on the fabric.
+    DuplicateId {
+        id: i32,
+    },
+    /// No claim with this ID is on the fabric.
+    UnknownId {
+        id: i32,
+    },
+    Invalid(FabricError),
+}
+
+impl fmt::Display for LiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveError::DuplicateId { id } => write!(f, "claim #{} is already placed", id),
+            LiveError::UnknownId { id } => write!(f, "claim #{} is not placed", id),
+            LiveError::Invalid(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for LiveError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Cell {
+    count: u32,
+    // Sum of the IDs of the claims on this square inch. While only one claim
+    // is on it, that's the claim's ID.
+    id_sum: i64,
+}
+
+/*  Fabric that claims can be added to and withdrawn from one at a time.
+
+Only claimed square inches are stored. Each claim also keeps how many of
+its square inches are shared with other claims, so it's intact exactly
+when that is 0. When a square inch goes from 1 to 2 claims (or back),
+the claim that was (or is left) alone on it is the cell's `id_sum`, which
+is all that's needed to keep every claim's shared count up to date.
+
+Inserting or removing a claim costs O(w * h), independent of how many
+other claims are placed.*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveFabric {
+    cells: HashMap<(i32, i32), Cell>,
+    claims: HashMap<i32, Claim>,
+    shared: HashMap<i32, usize>,
+    intact: BTreeSet<i32>,
+    overlap_area: usize,
+}
+
+impl LiveFabric {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, claim: Claim) -> Result<(), LiveError> {
+        if self.claims.contains_key(&claim.id) {
+            return Err(LiveError::DuplicateId { id: claim.id });
+        }
+        claim_end(&claim).map_err(LiveError::Invalid)?;
+
+        let mut shared = 0;
+
+        for square in squares(&claim) {
+            let cell = self.cells.entry(square).or_default();
+
+            match cell.count {
+                0 => {}
+                1 => {
+                    let owner = cell.id_sum as i32;
+                    let owner_shared = self.shared.entry(owner).or_default();
+                    *owner_shared += 1;
+                    if *owner_shared == 1 {
+                        self.intact.remove(&owner);
+                    }
+
+                    self.overlap_area += 1;
+                    shared += 1;
+                }
+                _ => shared += 1,
+            }
+
+            cell.count += 1;
+            cell.id_sum = cell.id_sum.wrapping_add(claim.id as i64);
+        }
+
+        if shared == 0 {
+            self.intact.insert(claim.id);
+        }
+        self.shared.insert(claim.id, shared);
+        self.claims.insert(claim.id, claim);
+
+        Ok(())
+    }
+
+    /// Withdraws the claim with ID `id` and returns it.
+    pub fn remove(&mut self, id: i32) -> Result<Claim, LiveError> {
+        let claim = self.claims.remove(&id).ok_or(LiveError::UnknownId { id })?;
+
+        for square in squares(&claim) {
+            let Some(cell) = self.cells.get_mut(&square) else {
+                continue;
+            };
+
+            cell.count -= 1;
+            cell.id_sum = cell.id_sum.wrapping_sub(id as i64);
+
+            match cell.count {
+                0 => {
+                    self.cells.remove(&square);
+                }
+                1 => {
+                    let owner = cell.id_sum as i32;
+                    let owner_shared = self.shared.entry(owner).or_default();
+                    *owner_shared -= 1;
+                    if *owner_shared == 0 {
+                        self.intact.insert(owner);
+                    }
+
+                    self.overlap_area -= 1;
+                }
+                _ => {}
+            }
+        }
+
+        self.shared.remove(&id);
+        self.intact.remove(&id);
+
+        Ok(claim)
+    }
+
+    /// Number of square inches within two or more claims.
+    pub fn overlap_area(&self) -> usize {
+        self.overlap_area
+    }
+
+    /// IDs of claims sharing no square inch with another, ascending.
+    pub fn intact_claims(&self) -> impl Iterator<Item = i32> + '_ {
+        self.intact.iter().copied()
+    }
+
+    pub fn is_intact(&self, id: i32) -> bool {
+        self.intact.contains(&id)
+    }
+
+    pub fn get(&self, id: i32) -> Option<&Claim> {
+        self.claims.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+
+    /// Number of claims on the square inch at (`x`, `y`).
+    pub fn count(&self, x: i32, y: i32) -> u32 {
+        self.cells.get(&(x, y)).map_or(0, |cell| cell.count)
+    }
+}
+
+fn squares(claim: &Claim) -> impl Iterator<Item = (i32, i32)> {
+    let (x, y, w, h) = (claim.x, claim.y, claim.w, claim.h);
+    (x..x + w).flat_map(move |i| (y..y + h).map(move |j| (i, j)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        claim::Claim,
+        live::{LiveError, LiveFabric},
+        sweep::sweep_overlaps,
+        testing::{claim, Rng},
+    };
+
+    #[test]
+    fn test_insert_then_remove() {
+        let mut live = LiveFabric::new();
+        live.insert(claim(1, 1, 3, 4, 4)).unwrap();
+        live.insert(claim(2, 3, 1, 4, 4)).unwrap();
+
+        let before = live.clone();
+        live.insert(claim(3, 2, 2, 4, 4)).unwrap();
+
+        assert_eq!(14, live.overlap_area());
+        assert_eq!(Ok(claim(3, 2, 2, 4, 4)), live.remove(3));
+        assert_eq!(before, live);
+
+        assert_eq!(4, live.overlap_area());
+        assert_eq!(Vec::<i32>::new(), live.intact_claims().collect::<Vec<_>>());
+
+        live.remove(2).unwrap();
+        live.remove(1).unwrap();
+        assert_eq!(LiveFabric::new(), live);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut live = LiveFabric::new();
+        live.insert(claim(1, 0, 0, 2, 2)).unwrap();
+
+        assert_eq!(
+            Err(LiveError::DuplicateId { id: 1 }),
+            live.insert(claim(1, 5, 5, 1, 1))
+        );
+        assert_eq!(Err(LiveError::UnknownId { id: 2 }), live.remove(2));
+        assert_eq!(1, live.len());
+    }
+
+    #[test]
+    fn test_matches_sweep() {
+        let mut rng = Rng::new(0x9e3779b97f4a7c15);
+
+        let mut live = LiveFabric::new();
+        let mut placed: Vec<Claim> = Vec::new();
+
+        for id in 1..=500 {
+            if !placed.is_empty() && rng.below(3) == 0 {
+                let removed = placed.remove(rng.below(placed.len() as u64) as usize);
+                live.remove(removed.id).unwrap();
+            } else {
+                let added = claim(id, rng.below(12), rng.below(12), rng.below(5), rng.below(5));
+                live.insert(added).unwrap();
+                placed.push(added);
+            }
+
+            let summary = sweep_overlaps(&placed);
+            let mut intact = summary.intact;
+            intact.sort_unstable();
+
+            assert_eq!(summary.overlap_area, live.overlap_area() as u128);
+            assert_eq!(intact, live.intact_claims().collect::<Vec<_>>());
+        }
+    }
+}
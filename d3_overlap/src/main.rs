@@ -1,125 +1,116 @@
-use regex::Regex;
-use std::{
-    fs,
-    io::{BufRead, BufReader},
+use day3_overlap::{
+    first_free_position, free_positions, parse_claims, render_ascii, render_image, Claim,
+    ConflictGraph, Fabric, ImageFormat, ParseMode,
 };
-
-struct Claim {
-    id: i32,
-    x: i32,
-    y: i32,
-    w: i32,
-    h: i32,
-}
+use std::{env, fs, process};
 
 fn main() {
     let claims: Vec<Claim> = get_input_as_vec();
-    let mut fabric: [[i32; 1000]; 1000] = [[0i32; 1000]; 1000];
-
-    // Mark each sq. inch of fabric with #claims
-    for claim in &claims {
-        let end_x = claim.x + claim.w;
-        let end_y = claim.y + claim.h;
 
-        for i in claim.x..end_x {
-            for j in claim.y..end_y {
-                fabric[i as usize][j as usize] += 1;
+    // Usage: day3_overlap [--conflicts json|dot]
+    //        day3_overlap --render PATH [--outline ID] [--ascii]
+    //        day3_overlap --place WxH [--all]
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some("--conflicts") {
+        let graph = ConflictGraph::from_claims(&claims);
+
+        match args.get(1).map(String::as_str) {
+            Some("json") => println!("{}", graph.to_json()),
+            Some("dot") => println!("{}", graph.to_dot()),
+            other => {
+                eprintln!(
+                    "Unknown conflict format {:?}, expected json or dot",
+                    other.unwrap_or("")
+                );
+                process::exit(1);
             }
         }
+        return;
     }
 
-    // Find claim with no overlap
-    for claim in &claims {
-        let end_x = claim.x + claim.w;
-        let end_y = claim.y + claim.h;
-        let mut found = true;
-
-        for i in claim.x..end_x {
-            for j in claim.y..end_y {
-                if fabric[i as usize][j as usize] != 1 {
-                    found = false;
-                    break;
-                }
-            }
-
-            if !found {
-                break;
-            }
+    // Mark each sq. inch of fabric with #claims
+    let fabric = match Fabric::from_claims(&claims) {
+        Ok(fabric) => fabric,
+        Err(err) => {
+            eprintln!("Failed to lay out fabric: {}", err);
+            process::exit(1);
         }
-
-        if found {
-            println!("{}", claim.id);
-            break;
+    };
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--render") {
+        let path = args.get(idx + 1).expect("Missing path after --render");
+        let outline = args
+            .iter()
+            .position(|arg| arg == "--outline")
+            .and_then(|idx| args.get(idx + 1))
+            .map(|id| id.parse::<i32>().expect("Failed to parse outline ID"))
+            .map(|id| {
+                claims
+                    .iter()
+                    .find(|claim| claim.id == id)
+                    .expect("No claim with outline ID")
+            });
+
+        let rendered = if args.iter().any(|arg| arg == "--ascii") {
+            render_ascii(&fabric, outline).map(String::into_bytes)
+        } else if path.ends_with(".png") {
+            render_image(&fabric, outline, ImageFormat::Png)
+        } else {
+            render_image(&fabric, outline, ImageFormat::Ppm)
+        };
+
+        match rendered {
+            Ok(bytes) => fs::write(path, bytes).expect("Failed to write render"),
+            Err(err) => eprintln!("Failed to render fabric: {}", err),
         }
+        return;
     }
-}
 
-fn get_input_as_vec() -> Vec<Claim> {
-    let mut claims: Vec<Claim> = Vec::new();
+    if let Some(idx) = args.iter().position(|arg| arg == "--place") {
+        let (w, h) = args
+            .get(idx + 1)
+            .and_then(|size| size.split_once('x'))
+            .and_then(|(w, h)| Some((w.parse::<usize>().ok()?, h.parse::<usize>().ok()?)))
+            .expect("Expected a size like 3x2 after --place");
+
+        let positions = if args.iter().any(|arg| arg == "--all") {
+            free_positions(&fabric, w, h)
+        } else {
+            first_free_position(&fabric, w, h).into_iter().collect()
+        };
+
+        if positions.is_empty() {
+            println!("No room for a {}x{} claim", w, h);
+        }
+        for (x, y) in positions {
+            println!("{},{}", x, y);
+        }
+        return;
+    }
 
-    // "#{id} @ {x},{y}: {w}x{h}"
-    static PATTERN: &str = r"#(?P<id>\d+)\s@\s(?P<x>\d+),(?P<y>\d+):\s(?P<w>\d+)x(?P<h>\d+)";
-    let rgx = Regex::new(PATTERN).expect("Failed to build regex.");
+    // Count sq. inches where #claims > 1
+    println!("Part 1: {}", fabric.overlap_area());
 
-    let file = fs::File::open("input.txt").expect("Failed to open file.");
-    let reader = BufReader::new(file);
+    // Find claims with no overlap
+    let intact: Vec<String> = fabric
+        .intact_claims()
+        .iter()
+        .map(|claim| claim.id.to_string())
+        .collect();
+    println!("Part 2: {}", intact.join(", "));
+}
 
-    for line in reader.lines() {
-        let text = line.expect("Failed to read line.");
-        let captures = rgx.captures(&text).expect("Failed to retrieve captures.");
+fn get_input_as_vec() -> Vec<Claim> {
+    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
 
-        claims.push(Claim {
-            id: captures
-                .get(1)
-                .expect("Failed to get `id` from capture.")
-                .as_str()
-                .to_owned()
-                .parse::<i32>()
-                .expect(""),
-            x: captures
-                .get(2)
-                .expect("Failed to get `x` from capture.")
-                .as_str()
-                .to_owned()
-                .parse::<i32>()
-                .expect(""),
-            y: captures
-                .get(3)
-                .expect("Failed to get `y` from capture.")
-                .as_str()
-                .to_owned()
-                .parse::<i32>()
-                .expect(""),
-            w: captures
-                .get(4)
-                .expect("Failed to get `w` from capture.")
-                .as_str()
-                .to_owned()
-                .parse::<i32>()
-                .expect(""),
-            h: captures
-                .get(5)
-                .expect("Failed to get `z` from capture.")
-                .as_str()
-                .to_owned()
-                .parse::<i32>()
-                .expect(""),
-        });
+    match parse_claims(&input, ParseMode::CollectAll) {
+        Ok(parsed) => parsed.claims,
+        Err(errors) => {
+            for err in &errors {
+                eprintln!("{}", err);
+            }
+            process::exit(1);
+        }
     }
-
-    claims
 }
-
-/* Part 1: Count sq. inches where #claims > 1
-
-   let mut num_overlaps = 0;
-   for i in 0..1000 {
-       for j in 0..1000 {
-           if fabric[i as usize][j as usize] > 1 {
-               num_overlaps += 1;
-           }
-       }
-   }
-
-   println!("{}", num_overlaps);
-*/
@@ -0,0 +1,182 @@
+use crate::claim::{Claim, ClaimError};
+use std::{collections::HashMap, error::Error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Stop at the first problem.
+    FailFast,
+    /// Check every line, then fail with all the problems found.
+    CollectAll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Malformed(ClaimError),
+    /// The claim's ID was already used on line `first_line`.
+    DuplicateId {
+        id: i32,
+        first_line: usize,
+    },
+    /// The claim has a width or height of 0.
+    ZeroArea {
+        id: i32,
+    },
+}
+
+/// A problem with one input line. `line` is 1-based; `text` is the line as
+/// written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}", self.line)?;
+
+        match self.kind {
+            ParseErrorKind::Malformed(err) => write!(f, ", {}", err)?,
+            ParseErrorKind::DuplicateId { id, first_line } => {
+                write!(f, ": claim #{} already on line {}", id, first_line)?
+            }
+            ParseErrorKind::ZeroArea { id } => write!(f, ": claim #{} has no area", id)?,
+        }
+
+        write!(f, " in {:?}", self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Claims parsed from an input file, with `lines[i]` the 1-based input line
+/// `claims[i]` came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedClaims {
+    pub claims: Vec<Claim>,
+    pub lines: Vec<usize>,
+}
+
+/*  Parses one claim per line, skipping blank lines.
+
+Besides malformed lines, claims reusing an earlier claim's ID and claims
+with no area are rejected: the first would make "the claim with ID n"
+ambiguous, and the second is always intact without claiming anything.*/
+pub fn parse_claims(input: &str, mode: ParseMode) -> Result<ParsedClaims, Vec<ParseError>> {
+    let mut parsed = ParsedClaims {
+        claims: Vec::new(),
+        lines: Vec::new(),
+    };
+    let mut errors: Vec<ParseError> = Vec::new();
+    let mut first_lines: HashMap<i32, usize> = HashMap::new();
+
+    for (idx, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let line = idx + 1;
+        let kind = match text.parse::<Claim>() {
+            Err(err) => Some(ParseErrorKind::Malformed(err)),
+            Ok(claim) => match first_lines.get(&claim.id) {
+                Some(&first_line) => Some(ParseErrorKind::DuplicateId {
+                    id: claim.id,
+                    first_line,
+                }),
+                None if claim.w == 0 || claim.h == 0 => {
+                    first_lines.insert(claim.id, line);
+                    Some(ParseErrorKind::ZeroArea { id: claim.id })
+                }
+                None => {
+                    first_lines.insert(claim.id, line);
+                    parsed.claims.push(claim);
+                    parsed.lines.push(line);
+                    None
+                }
+            },
+        };
+
+        if let Some(kind) = kind {
+            errors.push(ParseError {
+                line,
+                text: text.to_owned(),
+                kind,
+            });
+
+            if mode == ParseMode::FailFast {
+                break;
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        claim::{ClaimError, ClaimErrorKind, Token},
+        parse::{parse_claims, ParseErrorKind, ParseMode},
+    };
+
+    const EX_INPUT: &str = "#1 @ 1,3: 4x4
+
+#2 @ 3,1: 4x4
+#3 @ 5,5 2x2
+#1 @ 1,1: 1x1
+#4 @ 1,1: 0x3
+#5 @ 5,5: 2x2";
+
+    #[test]
+    fn test_collect_all() {
+        let errors = parse_claims(EX_INPUT, ParseMode::CollectAll).unwrap_err();
+        let found: Vec<(usize, ParseErrorKind)> =
+            errors.iter().map(|err| (err.line, err.kind)).collect();
+
+        assert_eq!(
+            vec![
+                (
+                    4,
+                    ParseErrorKind::Malformed(ClaimError {
+                        column: 10,
+                        kind: ClaimErrorKind::Expected(Token::Colon)
+                    })
+                ),
+                (
+                    5,
+                    ParseErrorKind::DuplicateId {
+                        id: 1,
+                        first_line: 1
+                    }
+                ),
+                (6, ParseErrorKind::ZeroArea { id: 4 }),
+            ],
+            found
+        );
+        assert_eq!("#3 @ 5,5 2x2", errors[0].text);
+    }
+
+    #[test]
+    fn test_fail_fast() {
+        let errors = parse_claims(EX_INPUT, ParseMode::FailFast).unwrap_err();
+
+        assert_eq!(1, errors.len());
+        assert_eq!(4, errors[0].line);
+    }
+
+    #[test]
+    fn test_valid() {
+        let parsed = parse_claims("#1 @ 1,3: 4x4\n\n#2 @ 3,1: 4x4\n", ParseMode::FailFast).unwrap();
+
+        assert_eq!(
+            vec![1, 2],
+            parsed.claims.iter().map(|c| c.id).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1, 3], parsed.lines);
+    }
+}
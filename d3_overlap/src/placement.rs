@@ -0,0 +1,169 @@
+use crate::fabric::Fabric;
+
+/*  Where a `w` by `h` claim fits on the fabric without overlapping any other.
+
+The fabric runs from (0, 0) to the far edges of its claims, and only
+positions with the whole claim inside it are considered. A 2D prefix sum
+over "is this square inch claimed" in the fabric's grid gives the number of
+claimed square inches in any rectangle in O(1), so each position costs
+O(1) regardless of the claim size. Everything outside the grid is free.
+
+Positions are the claim's top-left square inch, ordered top to bottom and
+then left to right. Zero-area claims aren't valid claims, so they fit
+nowhere.*/
+pub fn free_positions(fabric: &Fabric, w: usize, h: usize) -> Vec<(i32, i32)> {
+    FreeSpace::new(fabric, w, h)
+        .map(|space| space.positions().collect())
+        .unwrap_or_default()
+}
+
+/// The top-left-most position from `free_positions`, if any.
+pub fn first_free_position(fabric: &Fabric, w: usize, h: usize) -> Option<(i32, i32)> {
+    FreeSpace::new(fabric, w, h)?.positions().next()
+}
+
+// Claimed square inches in the rectangle [0, col) x [0, row) of the grid are
+// at `claimed[row * (width + 1) + col]`
+struct FreeSpace<'a> {
+    fabric: &'a Fabric,
+    w: i64,
+    h: i64,
+    claimed: Vec<usize>,
+}
+
+impl<'a> FreeSpace<'a> {
+    // `None` if a `w` by `h` claim doesn't fit on the fabric at all
+    fn new(fabric: &'a Fabric, w: usize, h: usize) -> Option<Self> {
+        let (right, bottom) = far_edges(fabric);
+        let w = i64::try_from(w).ok().filter(|&w| w > 0 && w <= right)?;
+        let h = i64::try_from(h).ok().filter(|&h| h > 0 && h <= bottom)?;
+
+        let (width, height) = (fabric.width(), fabric.height());
+        let stride = width + 1;
+        let mut claimed = vec![0usize; stride * (height + 1)];
+
+        for row in 0..height {
+            let mut in_row = 0;
+
+            for (col, &count) in fabric.row(row).iter().enumerate() {
+                in_row += usize::from(count > 0);
+                claimed[(row + 1) * stride + col + 1] = claimed[row * stride + col + 1] + in_row;
+            }
+        }
+
+        Some(FreeSpace {
+            fabric,
+            w,
+            h,
+            claimed,
+        })
+    }
+
+    fn positions(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let (right, bottom) = far_edges(self.fabric);
+
+        (0..=bottom - self.h)
+            .flat_map(move |y| (0..=right - self.w).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.claimed_in(x, y) == 0)
+            .map(|(x, y)| (x as i32, y as i32))
+    }
+
+    // Claimed square inches under a claim with its top-left at (`x`, `y`)
+    fn claimed_in(&self, x: i64, y: i64) -> usize {
+        let (origin_x, origin_y) = self.fabric.origin();
+        let (width, height) = (self.fabric.width() as i64, self.fabric.height() as i64);
+
+        // The claim's overlap with the grid, in grid coordinates
+        let left = (x - origin_x as i64).clamp(0, width) as usize;
+        let right = (x + self.w - origin_x as i64).clamp(0, width) as usize;
+        let top = (y - origin_y as i64).clamp(0, height) as usize;
+        let bottom = (y + self.h - origin_y as i64).clamp(0, height) as usize;
+
+        let stride = self.fabric.width() + 1;
+
+        self.claimed[bottom * stride + right] + self.claimed[top * stride + left]
+            - self.claimed[top * stride + right]
+            - self.claimed[bottom * stride + left]
+    }
+}
+
+// One past the rightmost and bottom-most square inches of the fabric
+fn far_edges(fabric: &Fabric) -> (i64, i64) {
+    let (origin_x, origin_y) = fabric.origin();
+
+    (
+        origin_x as i64 + fabric.width() as i64,
+        origin_y as i64 + fabric.height() as i64,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        claim::Claim,
+        fabric::Fabric,
+        placement::{first_free_position, free_positions},
+    };
+
+    #[test]
+    fn test_searches_from_origin() {
+        let claims = [Claim {
+            id: 1,
+            x: 500,
+            y: 500,
+            w: 3,
+            h: 3,
+        }];
+        let fabric = Fabric::from_claims(&claims).unwrap();
+
+        assert_eq!(Some((0, 0)), first_free_position(&fabric, 2, 2));
+        assert_eq!(Some((0, 0)), first_free_position(&fabric, 503, 500));
+        assert_eq!(None, first_free_position(&fabric, 503, 501));
+        assert_eq!(None, first_free_position(&fabric, 504, 1));
+    }
+
+    #[test]
+    fn test_free_positions() {
+        let claims = [
+            Claim {
+                id: 1,
+                x: 1,
+                y: 0,
+                w: 2,
+                h: 2,
+            },
+            Claim {
+                id: 2,
+                x: 0,
+                y: 2,
+                w: 1,
+                h: 1,
+            },
+        ];
+        let fabric = Fabric::from_claims(&claims).unwrap();
+
+        assert_eq!(
+            vec![(0, 0), (0, 1), (1, 2), (2, 2)],
+            free_positions(&fabric, 1, 1)
+        );
+        assert_eq!(vec![(0, 0)], free_positions(&fabric, 1, 2));
+        assert_eq!(vec![(1, 2)], free_positions(&fabric, 2, 1));
+        assert!(free_positions(&fabric, 2, 2).is_empty());
+    }
+
+    #[test]
+    fn test_zero_area() {
+        let claims = [Claim {
+            id: 1,
+            x: 0,
+            y: 0,
+            w: 3,
+            h: 3,
+        }];
+        let fabric = Fabric::from_claims(&claims).unwrap();
+
+        assert!(free_positions(&fabric, 0, 0).is_empty());
+        assert!(free_positions(&fabric, 0, 2).is_empty());
+        assert_eq!(None, first_free_position(&fabric, 2, 0));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{claim::Claim, fabric::Fabric};
+use std::{error::Error, fmt};
+
+/// Widest fabric `render_ascii` will draw, in square inches.
+pub const MAX_ASCII_WIDTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    /// The fabric has no square inches to draw.
+    Empty,
+    /// Too wide for ASCII, or too large for the image format.
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::Empty => write!(f, "fabric has nothing to render"),
+            RenderError::TooLarge { width, height } => {
+                write!(
+                    f,
+                    "fabric of {}x{} inches is too large to render",
+                    width, height
+                )
+            }
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+// Colour for an unclaimed square inch, then for counts scaled from 1 up to
+// the fabric's maximum
+const UNCLAIMED: [u8; 3] = [255, 255, 255];
+const SCALE: [[u8; 3]; 4] = [
+    [198, 219, 239],
+    [255, 237, 111],
+    [253, 141, 60],
+    [189, 0, 38],
+];
+const OUTLINE: [u8; 3] = [0, 160, 0];
+
+/*  Draws the fabric as text, one character per square inch:
+
+`.` unclaimed, `1`-`9` that many claims, `*` ten or more, and `#` on the
+border of the `outline` claim.*/
+pub fn render_ascii(fabric: &Fabric, outline: Option<&Claim>) -> Result<String, RenderError> {
+    if fabric.width() > MAX_ASCII_WIDTH {
+        return Err(RenderError::TooLarge {
+            width: fabric.width(),
+            height: fabric.height(),
+        });
+    }
+
+    let mut text = String::with_capacity((fabric.width() + 1) * fabric.height());
+
+    for row in 0..fabric.height() {
+        for (col, &count) in fabric.row(row).iter().enumerate() {
+            text.push(if on_outline(fabric, outline, col, row) {
+                '#'
+            } else {
+                match count {
+                    0 => '.',
+                    1..=9 => char::from_digit(count, 10).unwrap_or('?'),
+                    _ => '*',
+                }
+            });
+        }
+        text.push('\n');
+    }
+
+    Ok(text)
+}
+
+/// Draws the fabric as a heatmap, one pixel per square inch, coloured from
+/// pale blue (one claim) to dark red (the most claims on any square inch),
+/// with the border of the `outline` claim in green.
+pub fn render_image(
+    fabric: &Fabric,
+    outline: Option<&Claim>,
+    format: ImageFormat,
+) -> Result<Vec<u8>, RenderError> {
+    let (width, height) = (fabric.width(), fabric.height());
+
+    if width == 0 || height == 0 {
+        return Err(RenderError::Empty);
+    }
+
+    let fits = match format {
+        ImageFormat::Ppm => u32::try_from(width).is_ok() && u32::try_from(height).is_ok(),
+        ImageFormat::Png => png_idat_len(width, height).is_some(),
+    };
+    if !fits {
+        return Err(RenderError::TooLarge { width, height });
+    }
+
+    let max_count = fabric.max_count();
+    let mut rows: Vec<Vec<u8>> = Vec::with_capacity(height);
+
+    for row in 0..height {
+        let mut pixels: Vec<u8> = Vec::with_capacity(3 * width);
+
+        for (col, &count) in fabric.row(row).iter().enumerate() {
+            let colour = if on_outline(fabric, outline, col, row) {
+                OUTLINE
+            } else {
+                heat(count, max_count)
+            };
+            pixels.extend_from_slice(&colour);
+        }
+
+        rows.push(pixels);
+    }
+
+    Ok(match format {
+        ImageFormat::Ppm => encode_ppm(width, height, &rows),
+        ImageFormat::Png => encode_png(width as u32, height as u32, &rows),
+    })
+}
+
+fn on_outline(fabric: &Fabric, outline: Option<&Claim>, col: usize, row: usize) -> bool {
+    let Some(claim) = outline else {
+        return false;
+    };
+
+    let (origin_x, origin_y) = fabric.origin();
+    let x = origin_x as i64 + col as i64;
+    let y = origin_y as i64 + row as i64;
+    let (left, top) = (claim.x as i64, claim.y as i64);
+    let (right, bottom) = (left + claim.w as i64 - 1, top + claim.h as i64 - 1);
+
+    let inside = (left..=right).contains(&x) && (top..=bottom).contains(&y);
+    inside && (x == left || x == right || y == top || y == bottom)
+}
+
+// Linear interpolation through `SCALE`
+fn heat(count: u32, max_count: u32) -> [u8; 3] {
+    if count == 0 {
+        return UNCLAIMED;
+    }
+
+    if max_count <= 1 {
+        return SCALE[0];
+    }
+
+    let steps = (SCALE.len() - 1) as f64;
+    let t = (count - 1) as f64 / (max_count - 1) as f64 * steps;
+    let idx = (t.floor() as usize).min(SCALE.len() - 2);
+    let frac = t - idx as f64;
+
+    let mut colour = [0u8; 3];
+    for (channel, value) in colour.iter_mut().enumerate() {
+        let from = SCALE[idx][channel] as f64;
+        let to = SCALE[idx + 1][channel] as f64;
+        *value = (from + (to - from) * frac).round() as u8;
+    }
+
+    colour
+}
+
+fn encode_ppm(width: usize, height: usize, rows: &[Vec<u8>]) -> Vec<u8> {
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+    for row in rows {
+        image.extend_from_slice(row);
+    }
+
+    image
+}
+
+/*  Minimal PNG encoder: 8-bit RGB, no filtering, and the zlib stream made
+of uncompressed ("stored") deflate blocks, so no compression library is
+needed at the cost of file size.*/
+fn encode_png(width: u32, height: u32, rows: &[Vec<u8>]) -> Vec<u8> {
+    let mut raw: Vec<u8> = Vec::new();
+    for row in rows {
+        raw.push(0); // Filter type: none
+        raw.extend_from_slice(row);
+    }
+
+    let mut zlib: Vec<u8> = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(u16::MAX as usize).collect();
+
+    for (idx, block) in blocks.iter().enumerate() {
+        let len = block.len() as u16;
+        zlib.push(u8::from(idx == blocks.len() - 1));
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header: Vec<u8> = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut image: Vec<u8> = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    write_chunk(&mut image, b"IHDR", &header);
+    write_chunk(&mut image, b"IDAT", &zlib);
+    write_chunk(&mut image, b"IEND", &[]);
+
+    image
+}
+
+// PNG limits both dimensions and every chunk's length to 2^31 - 1
+const PNG_MAX: usize = i32::MAX as usize;
+
+// Length of the IDAT chunk `encode_png` writes, if it's within PNG's limits
+fn png_idat_len(width: usize, height: usize) -> Option<usize> {
+    if width > PNG_MAX || height > PNG_MAX {
+        return None;
+    }
+
+    let raw = width.checked_mul(3)?.checked_add(1)?.checked_mul(height)?;
+    let blocks = raw.div_ceil(u16::MAX as usize).max(1);
+    let zlib = raw
+        .checked_add(blocks.checked_mul(5)?)?
+        .checked_add(2 + 4)?;
+
+    Some(zlib).filter(|&len| len <= PNG_MAX)
+}
+
+fn write_chunk(image: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    image.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    image.extend_from_slice(kind);
+    image.extend_from_slice(data);
+
+    let crc = crc32(kind.iter().chain(data));
+    image.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod test {
+    use crate::render::{png_idat_len, PNG_MAX};
+
+    #[test]
+    fn test_png_limits() {
+        assert_eq!(Some(2 + 5 + 4 + 4), png_idat_len(1, 1));
+        assert_eq!(None, png_idat_len(PNG_MAX + 1, 1));
+        assert_eq!(None, png_idat_len(1, PNG_MAX + 1));
+        assert_eq!(None, png_idat_len(30_000, 30_000));
+        assert_eq!(None, png_idat_len(usize::MAX, usize::MAX));
+    }
+}
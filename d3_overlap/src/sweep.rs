@@ -0,0 +1,400 @@
+use crate::claim::Claim;
+
+/// Overlap summary computed without rasterizing the fabric.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepSummary {
+    /// Square inches within two or more claims.
+    pub overlap_area: u128,
+    /// IDs of claims that share no square inch with any other claim, in
+    /// input order. Zero-area claims are always intact.
+    pub intact: Vec<i32>,
+}
+
+/*  Overlap engine for claims spread over huge coordinates.
+
+A vertical line sweeps left to right over the claims' left and right
+edges, with the y coordinates compressed to the distinct edge values. Three
+segment trees over the compressed y axis follow the claims the line
+currently crosses:
+
+`cover`:  length of y covered by >= 1 and >= 2 claims, so each slab between
+          two consecutive x edges adds `len2 * width` to the overlap area
+`active`: number of claims on each y, so a claim starting where another is
+          still active is known to overlap it
+`stamps`: latest insertion order touching each y, so a claim leaving the
+          sweep knows whether anything started on its rows after it did
+
+Right edges are processed before left edges at the same x, since claims
+are half-open and merely touching claims don't overlap. That makes it
+O(n log n) in the number of claims, however large the fabric.*/
+pub fn sweep_overlaps(claims: &[Claim]) -> SweepSummary {
+    let rects: Vec<(usize, [i64; 4])> = claims
+        .iter()
+        .enumerate()
+        .filter(|(_, claim)| claim.w > 0 && claim.h > 0)
+        .map(|(idx, claim)| {
+            let (x, y) = (claim.x as i64, claim.y as i64);
+            (idx, [x, y, x + claim.w as i64, y + claim.h as i64])
+        })
+        .collect();
+
+    let mut ys: Vec<i64> = rects.iter().flat_map(|(_, r)| [r[1], r[3]]).collect();
+    ys.sort_unstable();
+    ys.dedup();
+
+    // (x, 0 = leaves / 1 = enters, claim), processed in sorted order
+    let mut events: Vec<(i64, u8, usize)> = rects
+        .iter()
+        .flat_map(|&(idx, r)| [(r[0], 1, idx), (r[2], 0, idx)])
+        .collect();
+    events.sort_unstable();
+
+    let segments = ys.len().saturating_sub(1);
+    let mut cover = CoverTree::new(&ys);
+    let mut active = AddTree::new(segments);
+    let mut stamps = ChmaxTree::new(segments);
+
+    let mut y_range = vec![(0, 0); claims.len()];
+    for &(idx, r) in &rects {
+        let lo = ys.binary_search(&r[1]).unwrap_or_default();
+        let hi = ys.binary_search(&r[3]).unwrap_or_default();
+        y_range[idx] = (lo, hi);
+    }
+
+    let mut overlapped = vec![false; claims.len()];
+    let mut entered_at = vec![0i64; claims.len()];
+    let mut overlap_area: u128 = 0;
+    let mut prev_x: Option<i64> = None;
+
+    for (stamp, &(x, kind, idx)) in events.iter().enumerate() {
+        if let Some(prev) = prev_x {
+            overlap_area += cover.len2() as u128 * (x - prev) as u128;
+        }
+        prev_x = Some(x);
+
+        let (lo, hi) = y_range[idx];
+
+        if kind == 1 {
+            if active.max(lo, hi) > 0 {
+                overlapped[idx] = true;
+            }
+
+            let stamp = stamp as i64 + 1;
+            entered_at[idx] = stamp;
+            stamps.chmax(lo, hi, stamp);
+            active.add(lo, hi, 1);
+            cover.add(lo, hi, 1);
+        } else {
+            if stamps.max(lo, hi) > entered_at[idx] {
+                overlapped[idx] = true;
+            }
+
+            active.add(lo, hi, -1);
+            cover.add(lo, hi, -1);
+        }
+    }
+
+    let intact = claims
+        .iter()
+        .zip(&overlapped)
+        .filter(|(_, &overlapped)| !overlapped)
+        .map(|(claim, _)| claim.id)
+        .collect();
+
+    SweepSummary {
+        overlap_area,
+        intact,
+    }
+}
+
+// Covered length of the compressed axis, at >= 1 and >= 2 claims deep
+struct CoverTree<'a> {
+    ys: &'a [i64],
+    count: Vec<i32>,
+    len1: Vec<i64>,
+    len2: Vec<i64>,
+}
+
+impl<'a> CoverTree<'a> {
+    fn new(ys: &'a [i64]) -> Self {
+        let size = 4 * ys.len().max(1);
+
+        CoverTree {
+            ys,
+            count: vec![0; size],
+            len1: vec![0; size],
+            len2: vec![0; size],
+        }
+    }
+
+    fn len2(&self) -> i64 {
+        self.len2[1]
+    }
+
+    // Adds `delta` to segments [lo, hi)
+    fn add(&mut self, lo: usize, hi: usize, delta: i32) {
+        if lo < hi {
+            self.update(1, 0, self.ys.len() - 1, lo, hi, delta);
+        }
+    }
+
+    fn update(&mut self, node: usize, l: usize, r: usize, lo: usize, hi: usize, delta: i32) {
+        if hi <= l || r <= lo {
+            return;
+        }
+
+        if lo <= l && r <= hi {
+            self.count[node] += delta;
+        } else {
+            let mid = (l + r) / 2;
+            self.update(2 * node, l, mid, lo, hi, delta);
+            self.update(2 * node + 1, mid, r, lo, hi, delta);
+        }
+
+        self.pull(node, l, r);
+    }
+
+    fn pull(&mut self, node: usize, l: usize, r: usize) {
+        let full = self.ys[r] - self.ys[l];
+        let leaf = r - l == 1;
+        let (child1, child2) = if leaf {
+            (0, 0)
+        } else {
+            (
+                self.len1[2 * node] + self.len1[2 * node + 1],
+                self.len2[2 * node] + self.len2[2 * node + 1],
+            )
+        };
+
+        (self.len1[node], self.len2[node]) = match self.count[node] {
+            0 => (child1, child2),
+            1 => (full, child1),
+            _ => (full, full),
+        };
+    }
+}
+
+// Range add / range max over [0, n). `add` applies to a node's whole range
+// and is already included in its `max`.
+struct AddTree {
+    n: usize,
+    add: Vec<i64>,
+    max: Vec<i64>,
+}
+
+impl AddTree {
+    fn new(n: usize) -> Self {
+        AddTree {
+            n,
+            add: vec![0; 4 * n.max(1)],
+            max: vec![0; 4 * n.max(1)],
+        }
+    }
+
+    fn add(&mut self, lo: usize, hi: usize, delta: i64) {
+        if lo < hi {
+            self.update(1, 0, self.n, lo, hi, delta);
+        }
+    }
+
+    fn max(&self, lo: usize, hi: usize) -> i64 {
+        if lo < hi {
+            self.query(1, 0, self.n, lo, hi)
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, node: usize, l: usize, r: usize, lo: usize, hi: usize, delta: i64) {
+        if hi <= l || r <= lo {
+            return;
+        }
+
+        if lo <= l && r <= hi {
+            self.add[node] += delta;
+            self.max[node] += delta;
+            return;
+        }
+
+        let mid = (l + r) / 2;
+        self.update(2 * node, l, mid, lo, hi, delta);
+        self.update(2 * node + 1, mid, r, lo, hi, delta);
+        self.max[node] = self.max[2 * node].max(self.max[2 * node + 1]) + self.add[node];
+    }
+
+    fn query(&self, node: usize, l: usize, r: usize, lo: usize, hi: usize) -> i64 {
+        if hi <= l || r <= lo {
+            return i64::MIN;
+        }
+
+        if lo <= l && r <= hi {
+            return self.max[node];
+        }
+
+        let mid = (l + r) / 2;
+        let children =
+            self.query(2 * node, l, mid, lo, hi)
+                .max(self.query(2 * node + 1, mid, r, lo, hi));
+
+        children.saturating_add(self.add[node])
+    }
+}
+
+// Range chmax / range max over [0, n). `floor` applies to a node's whole
+// range; `max` is the largest value within it.
+struct ChmaxTree {
+    n: usize,
+    floor: Vec<i64>,
+    max: Vec<i64>,
+}
+
+impl ChmaxTree {
+    fn new(n: usize) -> Self {
+        ChmaxTree {
+            n,
+            floor: vec![0; 4 * n.max(1)],
+            max: vec![0; 4 * n.max(1)],
+        }
+    }
+
+    fn chmax(&mut self, lo: usize, hi: usize, value: i64) {
+        if lo < hi {
+            self.update(1, 0, self.n, lo, hi, value);
+        }
+    }
+
+    fn max(&self, lo: usize, hi: usize) -> i64 {
+        if lo < hi {
+            self.query(1, 0, self.n, lo, hi)
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, node: usize, l: usize, r: usize, lo: usize, hi: usize, value: i64) {
+        if hi <= l || r <= lo {
+            return;
+        }
+
+        self.max[node] = self.max[node].max(value);
+
+        if lo <= l && r <= hi {
+            self.floor[node] = self.floor[node].max(value);
+            return;
+        }
+
+        let mid = (l + r) / 2;
+        self.update(2 * node, l, mid, lo, hi, value);
+        self.update(2 * node + 1, mid, r, lo, hi, value);
+    }
+
+    fn query(&self, node: usize, l: usize, r: usize, lo: usize, hi: usize) -> i64 {
+        if hi <= l || r <= lo {
+            return 0;
+        }
+
+        if lo <= l && r <= hi {
+            return self.max[node];
+        }
+
+        let mid = (l + r) / 2;
+        let children =
+            self.query(2 * node, l, mid, lo, hi)
+                .max(self.query(2 * node + 1, mid, r, lo, hi));
+
+        children.max(self.floor[node])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        claim::Claim,
+        fabric::Fabric,
+        sweep::sweep_overlaps,
+        testing::{claim, Rng},
+    };
+
+    #[test]
+    fn test_example() {
+        let claims = [
+            claim(1, 1, 3, 4, 4),
+            claim(2, 3, 1, 4, 4),
+            claim(3, 5, 5, 2, 2),
+        ];
+        let summary = sweep_overlaps(&claims);
+
+        assert_eq!(4, summary.overlap_area);
+        assert_eq!(vec![3], summary.intact);
+    }
+
+    #[test]
+    fn test_touching_edges() {
+        let claims = [
+            claim(1, 0, 0, 2, 2),
+            claim(2, 2, 0, 2, 2),
+            claim(3, 0, 2, 2, 2),
+            claim(4, 2, 2, 2, 2),
+        ];
+        let summary = sweep_overlaps(&claims);
+
+        assert_eq!(0, summary.overlap_area);
+        assert_eq!(vec![1, 2, 3, 4], summary.intact);
+    }
+
+    #[test]
+    fn test_nested() {
+        let claims = [
+            claim(1, 0, 0, 10, 10),
+            claim(2, 2, 2, 6, 6),
+            claim(3, 4, 4, 1, 1),
+            claim(4, 20, 20, 1, 1),
+        ];
+        let summary = sweep_overlaps(&claims);
+
+        assert_eq!(36, summary.overlap_area);
+        assert_eq!(vec![4], summary.intact);
+    }
+
+    #[test]
+    fn test_huge_coordinates() {
+        let claims = [
+            claim(1, 0, 0, i32::MAX, i32::MAX),
+            claim(2, i32::MAX - 2, i32::MAX - 3, 2, 2),
+        ];
+        let summary = sweep_overlaps(&claims);
+
+        assert_eq!(4, summary.overlap_area);
+        assert!(summary.intact.is_empty());
+    }
+
+    #[test]
+    fn test_matches_fabric() {
+        let mut rng = Rng::new(0x2545f4914f6cdd1d);
+
+        for _ in 0..500 {
+            let claims: Vec<Claim> = (0..rng.below(8))
+                .map(|id| {
+                    claim(
+                        id + 1,
+                        rng.below(10),
+                        rng.below(10),
+                        rng.below(6),
+                        rng.below(6),
+                    )
+                })
+                .collect();
+
+            let fabric = Fabric::from_claims(&claims).unwrap();
+            let summary = sweep_overlaps(&claims);
+            let intact: Vec<i32> = fabric
+                .intact_claims()
+                .iter()
+                .map(|claim| claim.id)
+                .collect();
+
+            assert_eq!(fabric.overlap_area() as u128, summary.overlap_area);
+            assert_eq!(intact, summary.intact, "{:?}", claims);
+        }
+    }
+}
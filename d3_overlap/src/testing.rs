@@ -0,0 +1,24 @@
+// Helpers shared by the unit tests
+
+use crate::claim::Claim;
+
+pub(crate) fn claim(id: i32, x: i32, y: i32, w: i32, h: i32) -> Claim {
+    Claim { id, x, y, w, h }
+}
+
+// Xorshift generator for reproducible random claims
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    // Next value in [0, bound)
+    pub(crate) fn below(&mut self, bound: u64) -> i32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound) as i32
+    }
+}
@@ -0,0 +1,280 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// `None` unless `month` and `day` name a real day of `year`.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    pub fn next_day(self) -> Self {
+        if self.day < days_in_month(self.year, self.month) {
+            Date {
+                day: self.day + 1,
+                ..self
+            }
+        } else if self.month < 12 {
+            Date {
+                month: self.month + 1,
+                day: 1,
+                ..self
+            }
+        } else {
+            Date {
+                year: self.year + 1,
+                month: 1,
+                day: 1,
+            }
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A date and time to the minute. Orders chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    pub date: Date,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl Timestamp {
+    /*  Date of the midnight hour this timestamp belongs to.
+
+    Guards on the night shift may start before midnight (e.g. at 23:58), so
+    anything from noon on counts towards the following night.*/
+    pub fn night(&self) -> Date {
+        if self.hour >= 12 {
+            self.date.next_day()
+        } else {
+            self.date
+        }
+    }
+
+    /// Minutes from the midnight starting `night()`; negative before it.
+    pub fn minute_of_night(&self) -> i32 {
+        let minutes = (self.hour * 60 + self.minute) as i32;
+
+        if self.hour >= 12 {
+            minutes - 24 * 60
+        } else {
+            minutes
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:02}:{:02}", self.date, self.hour, self.minute)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    BeginShift(u32),
+    FallsAsleep,
+    WakesUp,
+}
+
+/// "[YYYY-MM-DD hh:mm] Guard #ID begins shift", "... falls asleep" or
+/// "... wakes up".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    pub timestamp: Timestamp,
+    pub kind: EventKind,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] ", self.timestamp)?;
+
+        match self.kind {
+            EventKind::BeginShift(id) => write!(f, "Guard #{} begins shift", id),
+            EventKind::FallsAsleep => write!(f, "falls asleep"),
+            EventKind::WakesUp => write!(f, "wakes up"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventError {
+    /// No "[YYYY-MM-DD hh:mm]" at the start of the line.
+    MalformedTimestamp,
+    /// A timestamp naming a day or time that doesn't exist.
+    InvalidTimestamp,
+    /// Neither a shift change, falling asleep nor waking up.
+    UnknownEvent,
+    /// A guard ID that isn't a number, or doesn't fit a `u32`.
+    InvalidGuardId,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            EventError::MalformedTimestamp => "expected a [YYYY-MM-DD hh:mm] timestamp",
+            EventError::InvalidTimestamp => "no such date or time",
+            EventError::UnknownEvent => "unknown event",
+            EventError::InvalidGuardId => "invalid guard ID",
+        };
+
+        write!(f, "{}", reason)
+    }
+}
+
+impl Error for EventError {}
+
+impl FromStr for Event {
+    type Err = EventError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (stamp, rest) = s
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .ok_or(EventError::MalformedTimestamp)?;
+
+        let timestamp = parse_timestamp(stamp)?;
+
+        let kind = match rest.trim() {
+            "falls asleep" => EventKind::FallsAsleep,
+            "wakes up" => EventKind::WakesUp,
+            other => {
+                let id = other
+                    .strip_prefix("Guard #")
+                    .and_then(|rest| rest.strip_suffix(" begins shift"))
+                    .ok_or(EventError::UnknownEvent)?;
+
+                if !id.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(EventError::InvalidGuardId);
+                }
+
+                EventKind::BeginShift(id.parse().map_err(|_| EventError::InvalidGuardId)?)
+            }
+        };
+
+        Ok(Event { timestamp, kind })
+    }
+}
+
+// "YYYY-MM-DD hh:mm"
+fn parse_timestamp(stamp: &str) -> Result<Timestamp, EventError> {
+    let (date, time) = stamp
+        .split_once(' ')
+        .ok_or(EventError::MalformedTimestamp)?;
+
+    let date: Vec<&str> = date.split('-').collect();
+    let time: Vec<&str> = time.split(':').collect();
+
+    let ([year, month, day], [hour, minute]) = (&date[..], &time[..]) else {
+        return Err(EventError::MalformedTimestamp);
+    };
+
+    let field = |text: &str| number(text).ok_or(EventError::MalformedTimestamp);
+    let (year, month, day) = (field(year)?, field(month)?, field(day)?);
+    let (hour, minute) = (field(hour)?, field(minute)?);
+
+    let year = i32::try_from(year).map_err(|_| EventError::InvalidTimestamp)?;
+    let date = Date::new(year, month, day).ok_or(EventError::InvalidTimestamp)?;
+
+    if hour >= 24 || minute >= 60 {
+        return Err(EventError::InvalidTimestamp);
+    }
+
+    Ok(Timestamp { date, hour, minute })
+}
+
+// Unsigned run of digits
+fn number(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    text.parse().ok()
+}
+
+/// A log line that isn't an event. Since the log gets sorted, the error
+/// keeps the line's position in the file (from 1) and its original text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub kind: EventError,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} on line {}: {:?}", self.kind, self.line, self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Events in chronological order, with `lines[i]` the 1-based input line
+/// `events[i]` came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedEvents {
+    pub events: Vec<Event>,
+    pub lines: Vec<usize>,
+}
+
+/*  Parses one event per line, skipping blank lines, and sorts them by
+timestamp.
+
+Events at the same minute keep their input order. A log with a bad line
+can't be put in order reliably, so nothing is sorted unless every line
+parses; otherwise `Err` lists the bad lines in file order.*/
+pub fn parse_events(input: &str) -> Result<ParsedEvents, Vec<ParseError>> {
+    let mut parsed: Vec<(Event, usize)> = Vec::new();
+    let mut errors: Vec<ParseError> = Vec::new();
+
+    for (idx, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        match text.parse::<Event>() {
+            Ok(event) => parsed.push((event, idx + 1)),
+            Err(kind) => errors.push(ParseError {
+                line: idx + 1,
+                text: text.to_owned(),
+                kind,
+            }),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    parsed.sort_by_key(|(event, _)| event.timestamp);
+
+    Ok(ParsedEvents {
+        events: parsed.iter().map(|&(event, _)| event).collect(),
+        lines: parsed.iter().map(|&(_, line)| line).collect(),
+    })
+}
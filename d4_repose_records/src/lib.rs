@@ -0,0 +1,11 @@
+pub mod event;
+pub mod report;
+pub mod shift;
+pub mod validate;
+
+pub use event::{
+    parse_events, Date, Event, EventError, EventKind, ParseError, ParsedEvents, Timestamp,
+};
+pub use report::{report_to_csv, report_to_json, sleep_report, GuardReport};
+pub use shift::{get_sleep_frequencies, Shift, ShiftLog};
+pub use validate::{validate, Issue, IssueKind};
@@ -1,19 +1,64 @@
-use regex::Regex;
-use std::{collections::HashMap, fs};
+use day4_repose_records::{
+    get_sleep_frequencies, report_to_csv, report_to_json, sleep_report, validate, ShiftLog,
+};
+use std::{collections::HashMap, env, fs};
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 fn main() -> Result<()> {
     let input = fs::read_to_string("input.txt")?;
 
-    part1(&input)?;
-    part2(&input)?;
+    let log = match ShiftLog::parse(&input) {
+        Ok(log) => log,
+        Err(errors) => {
+            let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            return Err(messages.join("\n").into());
+        }
+    };
+
+    // Usage: day4_repose_records [--sorted PATH] [--check] [--report csv|json]
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--sorted") {
+        let path = args.get(idx + 1).ok_or("Missing path after --sorted")?;
+        log.write_sorted(path)?;
+    }
+
+    if args.iter().any(|arg| arg == "--check") {
+        let issues = validate(&log);
+
+        for issue in &issues {
+            eprintln!("{}", issue);
+        }
+        if !issues.is_empty() {
+            return Err(format!("{} inconsistencies in the log", issues.len()).into());
+        }
+    }
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--report") {
+        let reports = sleep_report(&log);
+
+        match args.get(idx + 1).map(String::as_str) {
+            Some("csv") => println!("{}", report_to_csv(&reports)),
+            Some("json") => println!("{}", report_to_json(&reports)),
+            other => {
+                let format = other.unwrap_or("");
+                return Err(
+                    format!("Unknown report format {:?}, expected csv or json", format).into(),
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    part1(&log)?;
+    part2(&log)?;
 
     Ok(())
 }
 
-fn part1(input: &str) -> Result<()> {
-    let shift_data = convert_input(input)?;
+fn part1(log: &ShiftLog) -> Result<()> {
+    let shift_data = log.by_guard();
 
     // Get guard with most time asleep
     let mut target_id = 0;
@@ -66,9 +111,8 @@ fn part1(input: &str) -> Result<()> {
     Ok(())
 }
 
-fn part2(input: &str) -> Result<()> {
-    let shift_data = convert_input(input)?;
-    let sleep_freq_data = get_sleep_frequencies(&shift_data)?;
+fn part2(log: &ShiftLog) -> Result<()> {
+    let sleep_freq_data = get_sleep_frequencies(&log.by_guard());
 
     let mut target_id: u32 = 0;
     let mut target_min: u32 = 0;
@@ -96,132 +140,6 @@ fn part2(input: &str) -> Result<()> {
     Ok(())
 }
 
-/*  Sleep schedules of guards stored in a hashmap:
-K: Guard ID (String)
-V: List of shifts that the guard worked. Each shift is a vec of
-   bools representing whether the guard was asleep at that minute.
-
-Example:
-ID  SCHEDULE (# = true | . = false)
-000000000011111111112222222222333333333344444444445555555555
-012345678901234567890123456789012345678901234567890123456789
-09  .............................................##########.....
-10  .....####################.....#########################.....
-........................#####...............................
-99  ........................................##########..........
-....................................##########..............
-
-Guard #10 was asleep from 00:05 until 00:25, and 00:30 until
-00:55 on shift #1, and asleep from 00:24 until 00:29 on shift #2.*/
-fn convert_input(input: &str) -> Result<HashMap<u32, Vec<Vec<bool>>>> {
-    // Sort events by timestamp
-    let mut events: Vec<&str> = input.lines().collect();
-    events.sort();
-
-    fs::write("sorted_input.txt", &events.join("\n"))?;
-
-    // Process events
-    static NEW_GUARD_PATTERN: &str = r"Guard\s#(?P<id>\d+)\sbegins\sshift";
-    let rgx_new_guard: Regex = Regex::new(NEW_GUARD_PATTERN)?;
-
-    let mut shift_data: HashMap<u32, Vec<Vec<bool>>> = HashMap::new();
-    let mut curr_guard_id = 0;
-    let mut curr_schedule: Option<Vec<bool>> = None;
-
-    for (idx, event) in events.iter().enumerate() {
-        match rgx_new_guard.captures(event) {
-            Some(capture) => {
-                //New shift begins
-
-                // If `curr_schedule` set, record last guard's shift before resetting
-                if let Some(s) = curr_schedule {
-                    shift_data
-                        .get_mut(&curr_guard_id)
-                        .expect("Failed to get schedule")
-                        .push(s);
-                }
-
-                // Reset current schedule and ID for new guard
-                curr_guard_id = capture[1].parse::<u32>().expect("Failed to parse guard id");
-                curr_schedule = None;
-
-                // Ensure entry in `shifts_worked`
-                shift_data
-                    .entry(curr_guard_id)
-                    .or_insert(Vec::new());
-            }
-            None => {
-                if event.contains("falls asleep") {
-                    // Record Sleep in `curr_schedule`
-
-                    // Grab existing schedule, or initialize
-                    let mut schedule = if let Some(sch) = curr_schedule {
-                        sch
-                    } else {
-                        vec![false; 60]
-                    };
-
-                    // Record time asleep
-                    static FIND_MINUTES: &str = r"\d{2}:(?P<min>\d{2})";
-                    let rgx_minutes: Regex = Regex::new(FIND_MINUTES).expect("Failed to create re");
-
-                    let sleeps_at: usize = rgx_minutes
-                        .captures(event)
-                        .expect("Failed to read starting timestamp")[1]
-                        .parse::<usize>()
-                        .expect("Failed to parse starting minute");
-
-                    let wakes_at: usize = rgx_minutes
-                        .captures(events[idx + 1])
-                        .expect("Failed to read ending timestamp")[1]
-                        .parse::<usize>()
-                        .expect("Failed to parse ending minute");
-
-                    let minutes_asleep = wakes_at - sleeps_at;
-
-                    for min in schedule.iter_mut().skip(sleeps_at).take(minutes_asleep) {
-                        *min = true;
-                    }
-
-                    curr_schedule = Some(schedule);
-                }
-            }
-        }
-    }
-
-    // Save final shift
-    if let Some(s) = curr_schedule {
-        shift_data
-            .get_mut(&curr_guard_id)
-            .expect("Failed to get schedule")
-            .push(s);
-    }
-
-    Ok(shift_data)
-}
-
-fn get_sleep_frequencies(
-    shift_data: &HashMap<u32, Vec<Vec<bool>>>,
-) -> Result<HashMap<u32, Vec<u32>>> {
-    let mut shift_freqs: HashMap<u32, Vec<u32>> = HashMap::new();
-
-    for (id, shift_list) in shift_data.iter() {
-        let mut freqs = vec![0u32; 60];
-
-        for shift in shift_list {
-            for (minute, was_asleep) in shift.iter().enumerate() {
-                if *was_asleep {
-                    freqs[minute] += 1;
-                }
-            }
-        }
-
-        shift_freqs.insert(*id, freqs);
-    }
-
-    Ok(shift_freqs)
-}
-
 #[allow(dead_code)] // Used for debugging
 fn output_shift_data(map: &HashMap<u32, Vec<Vec<bool>>>) -> Result<()> {
     let mut shift_data_printable: Vec<String> = vec![
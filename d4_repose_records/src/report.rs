@@ -0,0 +1,132 @@
+use crate::shift::{get_sleep_frequencies, ShiftLog};
+use std::collections::HashMap;
+
+/*  How one guard sleeps on duty.
+
+`average_asleep`:     minutes asleep per shift
+`longest_nap`:        longest single nap in minutes
+`most_likely_minute`: minute of the midnight hour they're most often asleep
+                      at (the earliest, on ties), or `None` if they never
+                      slept
+`probability`:        fraction of their shifts asleep at that minute
+`curve`:              fraction of their shifts asleep at each minute*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardReport {
+    pub guard: u32,
+    pub shifts: usize,
+    pub total_asleep: u32,
+    pub average_asleep: f64,
+    pub longest_nap: u32,
+    pub most_likely_minute: Option<u32>,
+    pub probability: f64,
+    pub curve: Vec<f64>,
+}
+
+/// One report per guard with at least one shift, by ascending guard ID.
+pub fn sleep_report(log: &ShiftLog) -> Vec<GuardReport> {
+    let shift_data = log.by_guard();
+    let sleep_freq_data = get_sleep_frequencies(&shift_data);
+
+    let mut longest_naps: HashMap<u32, u32> = HashMap::new();
+    for shift in log.shifts() {
+        let longest = shift.naps.iter().map(|nap| nap.end - nap.start).max();
+        let entry = longest_naps.entry(shift.guard).or_default();
+        *entry = (*entry).max(longest.unwrap_or(0));
+    }
+
+    let mut reports: Vec<GuardReport> = sleep_freq_data
+        .iter()
+        .map(|(&guard, freqs)| {
+            let shifts = shift_data[&guard].len();
+            let total_asleep: u32 = freqs.iter().sum();
+
+            let max = freqs.iter().copied().max().unwrap_or(0);
+            let most_likely_minute = freqs
+                .iter()
+                .position(|&count| count == max)
+                .filter(|_| max > 0)
+                .map(|minute| minute as u32);
+
+            GuardReport {
+                guard,
+                shifts,
+                total_asleep,
+                average_asleep: total_asleep as f64 / shifts as f64,
+                longest_nap: longest_naps[&guard],
+                most_likely_minute,
+                probability: max as f64 / shifts as f64,
+                curve: freqs
+                    .iter()
+                    .map(|&count| count as f64 / shifts as f64)
+                    .collect(),
+            }
+        })
+        .collect();
+
+    reports.sort_by_key(|report| report.guard);
+    reports
+}
+
+/// One row per guard, with the curve as columns `m00` to `m59`. A guard
+/// who never slept has an empty `most_likely_minute`.
+pub fn report_to_csv(reports: &[GuardReport]) -> String {
+    let mut header = String::from(
+        "guard,shifts,total_asleep,average_asleep,longest_nap,most_likely_minute,probability",
+    );
+    for minute in 0..60 {
+        header.push_str(&format!(",m{:02}", minute));
+    }
+
+    let mut rows = vec![header];
+
+    for report in reports {
+        let mut row = format!(
+            "{},{},{},{},{},{},{}",
+            report.guard,
+            report.shifts,
+            report.total_asleep,
+            report.average_asleep,
+            report.longest_nap,
+            report
+                .most_likely_minute
+                .map_or(String::new(), |minute| minute.to_string()),
+            report.probability,
+        );
+        for probability in &report.curve {
+            row.push_str(&format!(",{}", probability));
+        }
+
+        rows.push(row);
+    }
+
+    rows.join("\n")
+}
+
+/// A JSON array of one object per guard, with `most_likely_minute` null for
+/// a guard who never slept.
+pub fn report_to_json(reports: &[GuardReport]) -> String {
+    let objects: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let curve: Vec<String> = report.curve.iter().map(ToString::to_string).collect();
+
+            format!(
+                "{{\"guard\":{},\"shifts\":{},\"total_asleep\":{},\"average_asleep\":{},\
+                 \"longest_nap\":{},\"most_likely_minute\":{},\"probability\":{},\
+                 \"curve\":[{}]}}",
+                report.guard,
+                report.shifts,
+                report.total_asleep,
+                report.average_asleep,
+                report.longest_nap,
+                report
+                    .most_likely_minute
+                    .map_or(String::from("null"), |minute| minute.to_string()),
+                report.probability,
+                curve.join(","),
+            )
+        })
+        .collect();
+
+    format!("[{}]", objects.join(","))
+}
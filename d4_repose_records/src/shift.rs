@@ -0,0 +1,185 @@
+use crate::event::{parse_events, Date, Event, EventKind, ParseError, ParsedEvents};
+use std::{collections::HashMap, fs, io, ops::Range, path::Path};
+
+/// One guard's shift: the night it covers and the minutes of that night's
+/// midnight hour they slept through, as `start..end` ranges in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shift {
+    pub guard: u32,
+    pub night: Date,
+    pub naps: Vec<Range<u32>>,
+}
+
+impl Shift {
+    /// Whether the guard was asleep at each minute of the midnight hour.
+    pub fn asleep(&self) -> Vec<bool> {
+        let mut asleep = vec![false; 60];
+
+        for nap in &self.naps {
+            for minute in nap.clone() {
+                asleep[minute as usize] = true;
+            }
+        }
+
+        asleep
+    }
+
+    pub fn minutes_asleep(&self) -> u32 {
+        self.naps.iter().map(|nap| nap.end - nap.start).sum()
+    }
+}
+
+/*  The guards' log, sorted and split into shifts.
+
+Parsing has no side effects; the sorted log is only written out on request
+by `write_sorted`.
+
+Events before the first shift belong to no guard and don't show up in any
+shift, and neither do naps that never end. Only the midnight hour is
+tracked, so naps are cut to it.*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShiftLog {
+    events: Vec<Event>,
+    lines: Vec<usize>,
+    shifts: Vec<Shift>,
+}
+
+impl ShiftLog {
+    pub fn parse(input: &str) -> Result<Self, Vec<ParseError>> {
+        Ok(Self::from_events(parse_events(input)?))
+    }
+
+    pub fn from_events(parsed: ParsedEvents) -> Self {
+        let mut shifts: Vec<Shift> = Vec::new();
+        let mut asleep_since: Option<i32> = None;
+
+        for event in &parsed.events {
+            match (event.kind, shifts.last_mut()) {
+                (EventKind::BeginShift(guard), _) => {
+                    shifts.push(Shift {
+                        guard,
+                        night: event.timestamp.night(),
+                        naps: Vec::new(),
+                    });
+                    asleep_since = None;
+                }
+                // Before the first shift
+                (_, None) => {}
+                // Falling asleep again doesn't restart the nap
+                (EventKind::FallsAsleep, Some(_)) => {
+                    asleep_since.get_or_insert(event.timestamp.minute_of_night());
+                }
+                (EventKind::WakesUp, Some(shift)) => {
+                    let Some(sleeps_at) = asleep_since.take() else {
+                        continue;
+                    };
+
+                    let start = sleeps_at.clamp(0, 60) as u32;
+                    let end = event.timestamp.minute_of_night().clamp(0, 60) as u32;
+
+                    if start < end {
+                        shift.naps.push(start..end);
+                    }
+                }
+            }
+        }
+
+        ShiftLog {
+            events: parsed.events,
+            lines: parsed.lines,
+            shifts,
+        }
+    }
+
+    /// Every event, in chronological order.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// 1-based input line of each of `events()`.
+    pub fn lines(&self) -> &[usize] {
+        &self.lines
+    }
+
+    pub fn shifts(&self) -> &[Shift] {
+        &self.shifts
+    }
+
+    /// Each guard's shifts, as minute-by-minute asleep flags.
+    pub fn by_guard(&self) -> HashMap<u32, Vec<Vec<bool>>> {
+        let mut shift_data: HashMap<u32, Vec<Vec<bool>>> = HashMap::new();
+
+        for shift in &self.shifts {
+            shift_data
+                .entry(shift.guard)
+                .or_default()
+                .push(shift.asleep());
+        }
+
+        shift_data
+    }
+
+    /// Writes the events to `path`, one per line in chronological order.
+    pub fn write_sorted(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let lines: Vec<String> = self.events.iter().map(ToString::to_string).collect();
+        fs::write(path, lines.join("\n"))
+    }
+}
+
+/// Number of shifts each guard was asleep at each minute of the midnight
+/// hour.
+pub fn get_sleep_frequencies(shift_data: &HashMap<u32, Vec<Vec<bool>>>) -> HashMap<u32, Vec<u32>> {
+    let mut shift_freqs: HashMap<u32, Vec<u32>> = HashMap::new();
+
+    for (id, shift_list) in shift_data.iter() {
+        let mut freqs = vec![0u32; 60];
+
+        for shift in shift_list {
+            for (minute, was_asleep) in shift.iter().enumerate() {
+                if *was_asleep {
+                    freqs[minute] += 1;
+                }
+            }
+        }
+
+        shift_freqs.insert(*id, freqs);
+    }
+
+    shift_freqs
+}
+
+#[cfg(test)]
+mod test {
+    use crate::shift::ShiftLog;
+
+    #[test]
+    fn test_shifts() {
+        const EX_INPUT: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up";
+        let log = ShiftLog::parse(EX_INPUT).unwrap();
+        let shifts = log.shifts();
+
+        assert_eq!(2, shifts.len());
+        assert_eq!(vec![5..25, 30..55], shifts[0].naps);
+        assert_eq!(99, shifts[1].guard);
+        assert_eq!("1518-11-02", shifts[1].night.to_string());
+        assert_eq!(10, shifts[1].minutes_asleep());
+    }
+
+    #[test]
+    fn test_double_sleep() {
+        const EX_INPUT: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:10] falls asleep
+[1518-11-01 00:20] wakes up";
+        let log = ShiftLog::parse(EX_INPUT).unwrap();
+
+        assert_eq!(vec![5..20], log.shifts()[0].naps);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    event::{Date, Event, EventKind},
+    shift::ShiftLog,
+};
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Falling asleep or waking up before any guard began a shift.
+    BeforeFirstGuard,
+    /// Waking up without having fallen asleep.
+    WakeWithoutSleep,
+    /// Falling asleep while already asleep since line `since_line`.
+    AlreadyAsleep { since_line: usize },
+    /// Falling asleep and never waking up before the shift (or log) ends.
+    UnterminatedNap,
+    /// A shift on the same night as the one beginning on `other_line`.
+    OverlappingShift { other_line: usize },
+}
+
+/// An inconsistency in the log, found at the event on 1-based input
+/// `line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub line: usize,
+    pub event: Event,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            IssueKind::BeforeFirstGuard => write!(f, "event before any guard's shift")?,
+            IssueKind::WakeWithoutSleep => write!(f, "wakes up without falling asleep")?,
+            IssueKind::AlreadyAsleep { since_line } => {
+                write!(f, "falls asleep again, asleep since line {}", since_line)?
+            }
+            IssueKind::UnterminatedNap => write!(f, "falls asleep and never wakes up")?,
+            IssueKind::OverlappingShift { other_line } => {
+                write!(f, "shift on the same night as line {}", other_line)?
+            }
+        }
+
+        write!(f, " in \"{}\"", self.event)
+    }
+}
+
+/*  Walks the log as a state machine and reports every inconsistency.
+
+Between shifts nobody is on duty; a shift starts awake and alternates
+between asleep and awake until the next shift begins. Each night has one
+guard on duty for its midnight hour, so a second shift on the same night
+overlaps the first.
+
+Issues come out in chronological order of the events they're about.*/
+pub fn validate(log: &ShiftLog) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut nights: HashMap<Date, usize> = HashMap::new();
+
+    let mut on_duty = false;
+    // Index into the log of the falling asleep event, if asleep
+    let mut asleep: Option<usize> = None;
+
+    let events = log.events();
+    let lines = log.lines();
+    let issue = |idx: usize, kind: IssueKind| Issue {
+        line: lines[idx],
+        event: events[idx],
+        kind,
+    };
+
+    for (idx, event) in events.iter().enumerate() {
+        match event.kind {
+            EventKind::BeginShift(_) => {
+                if let Some(sleep_idx) = asleep.take() {
+                    issues.push(issue(sleep_idx, IssueKind::UnterminatedNap));
+                }
+
+                let night = event.timestamp.night();
+                if let Some(&other_line) = nights.get(&night) {
+                    issues.push(issue(idx, IssueKind::OverlappingShift { other_line }));
+                } else {
+                    nights.insert(night, lines[idx]);
+                }
+
+                on_duty = true;
+            }
+            _ if !on_duty => issues.push(issue(idx, IssueKind::BeforeFirstGuard)),
+            EventKind::FallsAsleep => match asleep {
+                Some(sleep_idx) => issues.push(issue(
+                    idx,
+                    IssueKind::AlreadyAsleep {
+                        since_line: lines[sleep_idx],
+                    },
+                )),
+                None => asleep = Some(idx),
+            },
+            EventKind::WakesUp => {
+                if asleep.take().is_none() {
+                    issues.push(issue(idx, IssueKind::WakeWithoutSleep));
+                }
+            }
+        }
+    }
+
+    if let Some(sleep_idx) = asleep {
+        issues.push(issue(sleep_idx, IssueKind::UnterminatedNap));
+    }
+
+    issues.sort_by_key(|issue| (issue.event.timestamp, issue.line));
+    issues
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        shift::ShiftLog,
+        validate::{validate, IssueKind},
+    };
+
+    #[test]
+    fn test_valid_log() {
+        const EX_INPUT: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up";
+
+        assert!(validate(&ShiftLog::parse(EX_INPUT).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_issues() {
+        // The first event is at the bottom, so lines and log order differ
+        const EX_INPUT: &str = "[1518-01-01 23:58] Guard #1 begins shift
+[1518-01-02 00:05] falls asleep
+[1518-01-02 00:07] falls asleep
+[1518-01-02 00:09] wakes up
+[1518-01-02 00:10] wakes up
+[1518-01-02 00:20] falls asleep
+[1518-01-02 00:30] Guard #2 begins shift
+[1518-01-03 00:00] Guard #3 begins shift
+[1518-01-03 00:20] falls asleep
+[1518-01-01 00:01] falls asleep";
+
+        let found: Vec<(usize, IssueKind)> = validate(&ShiftLog::parse(EX_INPUT).unwrap())
+            .iter()
+            .map(|issue| (issue.line, issue.kind))
+            .collect();
+
+        assert_eq!(
+            vec![
+                (10, IssueKind::BeforeFirstGuard),
+                (3, IssueKind::AlreadyAsleep { since_line: 2 }),
+                (5, IssueKind::WakeWithoutSleep),
+                (6, IssueKind::UnterminatedNap),
+                (7, IssueKind::OverlappingShift { other_line: 1 }),
+                (9, IssueKind::UnterminatedNap),
+            ],
+            found
+        );
+    }
+}